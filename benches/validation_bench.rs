@@ -8,13 +8,17 @@ fn bench_validation(c: &mut Criterion) {
         tools: vec![
             ToolCheck {
                 name: "node".to_string(),
+                path: None,
                 version: Some(">=18.0.0".to_string()),
                 required: true,
+                when: None,
             },
             ToolCheck {
                 name: "git".to_string(),
+                path: None,
                 version: None,
                 required: true,
+                when: None,
             },
         ],
         env_vars: vec![
@@ -22,17 +26,20 @@ fn bench_validation(c: &mut Criterion) {
                 name: "PATH".to_string(),
                 required: true,
                 pattern: None,
+                when: None,
             },
         ],
-        ports: vec![3000, 5432, 6379, 8080],
+        ports: vec![3000.into(), 5432.into(), 6379.into(), 8080.into()],
         files: vec![
             FileCheck {
                 path: "Cargo.toml".to_string(),
                 required: true,
                 is_directory: false,
                 permissions: None,
+                ..Default::default()
             },
         ],
+        ..Default::default()
     };
 
     c.bench_function("run_all_validations", |b| {
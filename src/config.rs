@@ -1,30 +1,400 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
+use std::net::IpAddr;
 use std::path::Path;
+use std::time::Duration;
 use anyhow::{Context, Result};
+use crate::units::{parse_duration, parse_size};
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct Config {
     pub version: String,
     #[serde(default)]
     pub tools: Vec<ToolCheck>,
     #[serde(default)]
     pub env_vars: Vec<EnvVarCheck>,
-    #[serde(default)]
-    pub ports: Vec<u16>,
+    #[serde(default, deserialize_with = "deserialize_ports")]
+    pub ports: Vec<PortCheck>,
     #[serde(default)]
     pub files: Vec<FileCheck>,
     #[serde(default)]
     pub network: Vec<NetworkCheck>,
+    #[serde(default)]
+    pub commands: Vec<CommandCheck>,
+    #[serde(default)]
+    pub system: Option<SystemCheck>,
+    #[serde(default)]
+    pub os: Option<OsCheck>,
+    #[serde(default)]
+    pub git: Option<GitCheck>,
+    #[serde(default)]
+    pub docker: Option<DockerCheck>,
+    #[serde(default)]
+    pub databases: Vec<DatabaseCheck>,
+    #[serde(default)]
+    pub libraries: Vec<LibraryCheck>,
+    #[serde(default)]
+    pub sysctl: Vec<SysctlCheck>,
+    #[serde(default)]
+    pub limits: Vec<LimitCheck>,
+    #[serde(default)]
+    pub processes: Vec<ProcessCheck>,
+    #[serde(default)]
+    pub services: Vec<ServiceCheck>,
+    #[serde(default)]
+    pub python: Option<PythonCheck>,
+    #[serde(default)]
+    pub node: Option<NodeCheck>,
+    #[serde(default)]
+    pub ruby: Option<RubyCheck>,
+    #[serde(default)]
+    pub go: Option<GoCheck>,
+    #[serde(default)]
+    pub cargo: Option<CargoCheck>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ToolCheck {
     pub name: String,
+    /// Explicit path to the binary instead of looking it up on PATH
+    #[serde(default)]
+    pub path: Option<String>,
     #[serde(default)]
     pub version: Option<String>,
     #[serde(default = "default_true")]
     pub required: bool,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+/// An arbitrary command whose exit code and output are checked.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CommandCheck {
+    /// Command line, split into arguments unless `shell` is set
+    pub run: String,
+    /// Run through `sh -c` (or `cmd /C` on Windows)
+    #[serde(default)]
+    pub shell: bool,
+    /// Expected exit code (defaults to 0)
+    #[serde(default)]
+    pub expect_exit: Option<i32>,
+    /// Regex the standard output must match
+    #[serde(default)]
+    pub stdout_matches: Option<String>,
+    /// Regex the standard error must match
+    #[serde(default)]
+    pub stderr_matches: Option<String>,
+    /// Kill the command after this long (defaults to 30s)
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    pub timeout: Option<Duration>,
+    /// Working directory
+    #[serde(default)]
+    pub cwd: Option<String>,
+    #[serde(default = "default_true")]
+    pub required: bool,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+// Matches the serde defaults, so `..Default::default()` builds a required check
+impl Default for CommandCheck {
+    fn default() -> Self {
+        Self {
+            run: String::new(),
+            shell: false,
+            expect_exit: None,
+            stdout_matches: None,
+            stderr_matches: None,
+            timeout: None,
+            cwd: None,
+            required: true,
+            when: None,
+        }
+    }
+}
+
+/// Expected operating system, distribution, kernel and architecture.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct OsCheck {
+    /// Accepted operating systems (`linux`, `macos`, `windows`) or families (`unix`)
+    #[serde(default, deserialize_with = "deserialize_strings")]
+    pub family: Vec<String>,
+    /// Accepted distributions, matched against `ID` and `ID_LIKE` in /etc/os-release
+    #[serde(default, deserialize_with = "deserialize_strings")]
+    pub distro: Vec<String>,
+    /// Version requirement for the distribution's `VERSION_ID`, e.g. ">=22.04"
+    #[serde(default)]
+    pub distro_version: Option<String>,
+    /// Version requirement for the kernel release, e.g. ">=5.10"
+    #[serde(default)]
+    pub kernel: Option<String>,
+    /// Accepted CPU architectures (`x86_64`, `aarch64`, ...)
+    #[serde(default, deserialize_with = "deserialize_strings")]
+    pub arch: Vec<String>,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+/// Restricts a check to matching platforms; every listed field must match.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Condition {
+    /// Operating systems (`linux`, `macos`, `windows`) or families (`unix`)
+    #[serde(default, deserialize_with = "deserialize_strings")]
+    pub os: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_strings")]
+    pub arch: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_strings")]
+    pub distro: Vec<String>,
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = [("os", &self.os), ("arch", &self.arch), ("distro", &self.distro)]
+            .iter()
+            .filter(|(_, values)| !values.is_empty())
+            .map(|(name, values)| format!("{}: {}", name, values.join(" or ")))
+            .collect();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// Expected state of the project's git repository.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct GitCheck {
+    /// Repository directory (defaults to the current directory)
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Hooks that must be installed, looked up in `core.hooksPath` or `.git/hooks`
+    #[serde(default, deserialize_with = "deserialize_strings")]
+    pub hooks: Vec<String>,
+    /// Require `user.email` to be set
+    #[serde(default)]
+    pub user_email: bool,
+    /// Regex `user.email` must match
+    #[serde(default)]
+    pub user_email_matches: Option<String>,
+    /// Require submodules to be initialized and at their recorded commits
+    #[serde(default)]
+    pub submodules: bool,
+    /// Require Git LFS objects to be pulled
+    #[serde(default)]
+    pub lfs: bool,
+    /// Allowed branches; glob patterns like `release/*` are supported
+    #[serde(default, deserialize_with = "deserialize_strings")]
+    pub branch: Vec<String>,
+    /// Require a worktree without uncommitted changes
+    #[serde(default)]
+    pub clean: bool,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+/// A Python virtualenv and the packages installed in it.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct PythonCheck {
+    /// Virtualenv directory (defaults to `$VIRTUAL_ENV`, then `.venv` or `venv`)
+    #[serde(default)]
+    pub venv: Option<String>,
+    /// Version requirement for the virtualenv's interpreter
+    #[serde(default)]
+    pub version: Option<String>,
+    /// requirements.txt or pyproject.toml files whose packages must be installed
+    /// (defaults to whichever of the two exists)
+    #[serde(default, deserialize_with = "deserialize_strings")]
+    pub requirements: Vec<String>,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+/// A Node.js project's installed dependencies, `engines` and `packageManager`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NodeCheck {
+    /// Directory containing package.json (defaults to the current directory)
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Compare node_modules against the lockfile
+    #[serde(default = "default_true")]
+    pub dependencies: bool,
+    /// Check `engines.node` against the installed node
+    #[serde(default = "default_true")]
+    pub engines: bool,
+    /// Check that the `packageManager` in package.json is the one available
+    #[serde(default = "default_true")]
+    pub package_manager: bool,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+/// A Ruby project whose Gemfile.lock gems must be installed.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct RubyCheck {
+    /// Directory containing the Gemfile (defaults to the current directory)
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+/// A Go module whose go.sum and module cache must cover go.mod.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct GoCheck {
+    /// Directory containing go.mod (defaults to the current directory)
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+/// A Rust package whose Cargo.lock must match Cargo.toml.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct CargoCheck {
+    /// Directory containing Cargo.toml (defaults to the current directory)
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+/// Docker daemon reachability, version, local images and compose services.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct DockerCheck {
+    /// Daemon address (`unix:///path` or `tcp://host:port`); defaults to DOCKER_HOST or /var/run/docker.sock
+    #[serde(default)]
+    pub host: Option<String>,
+    /// Version requirement for the Docker server
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Images that must be present locally
+    #[serde(default, deserialize_with = "deserialize_strings")]
+    pub images: Vec<String>,
+    /// Compose file whose services are reported
+    #[serde(default)]
+    pub compose_file: Option<String>,
+    /// Compose services that must be running
+    #[serde(default, deserialize_with = "deserialize_strings")]
+    pub services: Vec<String>,
+    /// Compose project the services belong to; defaults to COMPOSE_PROJECT_NAME, the
+    /// compose file's `name` or its directory name, like `docker compose` does
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+/// A PostgreSQL, MySQL or Redis server to connect and authenticate to.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct DatabaseCheck {
+    /// Connection URL such as `${DATABASE_URL}` or `redis://localhost:6379/0`
+    pub url: String,
+    /// Version requirement for the server
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Database that must exist (defaults to the one in the URL)
+    #[serde(default)]
+    pub database: Option<String>,
+    /// PostgreSQL schema that must exist in the database
+    #[serde(default)]
+    pub schema: Option<String>,
+    #[serde(flatten)]
+    pub retry: RetryConfig,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+/// A native library that must be installed for builds that link against it.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LibraryCheck {
+    /// pkg-config module name such as `openssl` or `libpq`
+    pub name: String,
+    /// Version requirement, checked against the `.pc` file
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Header files that must be on the include path, such as `openssl/ssl.h`
+    #[serde(default, deserialize_with = "deserialize_strings")]
+    pub headers: Vec<String>,
+    #[serde(default = "default_true")]
+    pub required: bool,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+/// A kernel tunable read from /proc/sys, such as `vm.max_map_count`.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct SysctlCheck {
+    pub name: String,
+    #[serde(default)]
+    pub min: Option<u64>,
+    #[serde(default)]
+    pub max: Option<u64>,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+/// A resource limit of the current process: `nofile`, `nproc`, `memlock`, `stack` or `core`.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct LimitCheck {
+    pub name: String,
+    /// Minimum soft limit; a number, a size like "64MiB" or "unlimited"
+    #[serde(default, deserialize_with = "deserialize_limit")]
+    pub min: Option<u64>,
+    #[serde(default, deserialize_with = "deserialize_limit")]
+    pub max: Option<u64>,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+/// A process that must be running, matched by `name` and/or a `matches` regex.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ProcessCheck {
+    /// Executable name, compared to the process name and the basename of argv[0]
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Regex matched against the full command line
+    #[serde(default)]
+    pub matches: Option<String>,
+    #[serde(default = "default_min_count")]
+    pub min_count: usize,
+    /// User name or uid that must own the process
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+/// A systemd unit that must be active.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct ServiceCheck {
+    /// Unit name; `.service` is added when there is no suffix
+    pub name: String,
+    /// Check the user's service manager (`systemctl --user`)
+    #[serde(default)]
+    pub user: bool,
+    /// Also require the unit to be enabled at boot or login
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+/// Minimum machine resources.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct SystemCheck {
+    /// Minimum free disk space on `path`
+    #[serde(default, deserialize_with = "deserialize_size")]
+    pub min_free_disk: Option<u64>,
+    /// Path whose filesystem is checked for free space (defaults to the current directory)
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Minimum total memory, as installed; up to 10% below is accepted for kernel reservations
+    #[serde(default, deserialize_with = "deserialize_size")]
+    pub min_memory: Option<u64>,
+    /// Minimum number of CPUs available to this process
+    #[serde(default)]
+    pub min_cpus: Option<usize>,
+    #[serde(default)]
+    pub when: Option<Condition>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -34,34 +404,473 @@ pub struct EnvVarCheck {
     pub required: bool,
     #[serde(default)]
     pub pattern: Option<String>,
+    #[serde(default)]
+    pub when: Option<Condition>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct FileCheck {
+    /// Literal path or glob pattern such as `migrations/*.sql` or `**/*.pem`
     pub path: String,
     #[serde(default = "default_true")]
     pub required: bool,
     #[serde(default = "default_false")]
     pub is_directory: bool,
+    /// Require the path to be (or not be) a symlink
+    #[serde(default)]
+    pub symlink: Option<bool>,
+    /// Expected symlink target; implies `symlink: true`
     #[serde(default)]
+    pub target: Option<String>,
+    /// Exact mode, e.g. "0600"
+    #[serde(default, deserialize_with = "deserialize_mode")]
     pub permissions: Option<u32>,
+    /// Mask of bits the mode may contain; stricter modes pass
+    #[serde(default, deserialize_with = "deserialize_mode")]
+    pub max_permissions: Option<u32>,
+    /// Bits the mode must contain
+    #[serde(default, deserialize_with = "deserialize_mode")]
+    pub min_permissions: Option<u32>,
+    #[serde(default)]
+    pub executable: Option<bool>,
+    /// Expected owner, by user name or uid
+    #[serde(default, deserialize_with = "deserialize_id")]
+    pub owner: Option<String>,
+    /// Expected group, by group name or gid
+    #[serde(default, deserialize_with = "deserialize_id")]
+    pub group: Option<String>,
+    /// Minimum number of glob matches (defaults to 1 when required)
+    #[serde(default)]
+    pub min_count: Option<usize>,
+    /// Maximum number of glob matches
+    #[serde(default)]
+    pub max_count: Option<usize>,
+    /// Substrings that must appear somewhere in the file
+    #[serde(default)]
+    pub contains: Vec<String>,
+    /// Substrings that must not appear anywhere in the file
+    #[serde(default)]
+    pub not_contains: Vec<String>,
+    /// Regex patterns that must match the file contents
+    #[serde(default)]
+    pub matches: Vec<String>,
+    /// Regex patterns that must not match the file contents
+    #[serde(default)]
+    pub not_matches: Vec<String>,
+    /// Expected SHA-256 digest, hex encoded
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Expected SHA-512 digest, hex encoded
+    #[serde(default)]
+    pub sha512: Option<String>,
+    /// Minimum size in bytes or with units ("1KiB")
+    #[serde(default, deserialize_with = "deserialize_size")]
+    pub min_size: Option<u64>,
+    /// Maximum size in bytes or with units ("10MB")
+    #[serde(default, deserialize_with = "deserialize_size")]
+    pub max_size: Option<u64>,
+    /// Maximum time since the last modification ("24h")
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    pub max_age: Option<Duration>,
+    /// Path of a file this one must have been modified after
+    #[serde(default)]
+    pub newer_than: Option<String>,
+    /// Format used to parse the file for `keys` checks (inferred from the extension if omitted)
+    #[serde(default)]
+    pub format: Option<FileFormat>,
+    /// Structured checks on values inside JSON, YAML or TOML files
+    #[serde(default)]
+    pub keys: Vec<KeyCheck>,
+    #[serde(default)]
+    pub when: Option<Condition>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+// Matches the serde defaults, so `..Default::default()` builds a required check
+impl Default for FileCheck {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            required: true,
+            is_directory: false,
+            symlink: None,
+            target: None,
+            permissions: None,
+            max_permissions: None,
+            min_permissions: None,
+            executable: None,
+            owner: None,
+            group: None,
+            min_count: None,
+            max_count: None,
+            contains: Vec::new(),
+            not_contains: Vec::new(),
+            matches: Vec::new(),
+            not_matches: Vec::new(),
+            sha256: None,
+            sha512: None,
+            min_size: None,
+            max_size: None,
+            max_age: None,
+            newer_than: None,
+            format: None,
+            keys: Vec::new(),
+            when: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct KeyCheck {
+    /// Selector such as `engines.node` or `services.web.ports[0]`
+    pub key: String,
+    #[serde(default)]
+    pub equals: Option<serde_json::Value>,
+    #[serde(default)]
+    pub matches: Option<String>,
+    #[serde(default)]
+    pub exists: Option<bool>,
+    #[serde(default)]
+    pub semver: Option<String>,
+}
+
+/// A port entry: either a bare port number (which must be free) or a mapping.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct PortCheck {
+    pub port: u16,
+    /// Host to probe or bind (defaults to 127.0.0.1)
+    #[serde(default)]
+    pub host: Option<String>,
+    /// Require something to be listening instead of the port being free
+    #[serde(default = "default_false")]
+    pub listening: bool,
+    #[serde(flatten)]
+    pub retry: RetryConfig,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+impl From<u16> for PortCheck {
+    fn from(port: u16) -> Self {
+        Self { port, ..Default::default() }
+    }
+}
+
+/// Timeout and retry settings shared by network, port and database checks.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct RetryConfig {
+    /// Per-attempt timeout
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    pub timeout: Option<Duration>,
+    /// Number of retries after a failed attempt
+    #[serde(default)]
+    pub retries: u32,
+    /// Delay before the first retry (defaults to 1s)
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    pub retry_interval: Option<Duration>,
+    #[serde(default)]
+    pub backoff: Backoff,
+    /// Keep retrying until the check passes or this much time has passed
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    pub wait_until_ready: Option<Duration>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Backoff {
+    #[default]
+    Fixed,
+    Exponential,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct NetworkCheck {
+    /// Endpoint to probe: an `http(s)://` URL or `tcp://host:port`
+    #[serde(default)]
     pub url: String,
+    /// Hostname that must resolve through the system resolver (including /etc/hosts)
+    #[serde(default)]
+    pub dns: Option<String>,
+    /// Addresses `dns` must resolve to
+    #[serde(default, deserialize_with = "deserialize_addresses")]
+    pub resolves_to: Vec<IpAddr>,
+    #[serde(flatten)]
+    pub retry: RetryConfig,
     #[serde(default)]
     pub status_code: Option<u16>,
+    /// Accepted statuses: a code, a class ("2xx"), a range ("200-299") or a list of these
+    #[serde(default, deserialize_with = "deserialize_statuses")]
+    pub status: Vec<StatusPattern>,
+    /// Follow redirects (defaults to true unless `expect_redirect` is set)
+    #[serde(default)]
+    pub follow_redirects: Option<bool>,
+    /// Expected redirect target from the `Location` header
+    #[serde(default)]
+    pub expect_redirect: Option<String>,
+    /// Warn when the server certificate expires within this many days (defaults to 14)
+    #[serde(default)]
+    pub cert_warn_days: Option<u32>,
+    /// PEM bundle of extra CA certificates to trust
+    #[serde(default)]
+    pub ca_bundle: Option<String>,
+    /// PEM client certificate for mTLS; may also contain the private key
+    #[serde(default)]
+    pub client_cert: Option<String>,
+    /// PEM private key for `client_cert`
+    #[serde(default)]
+    pub client_key: Option<String>,
+    /// Proxy URL; defaults to HTTP_PROXY/HTTPS_PROXY/ALL_PROXY from the environment
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// Hosts to connect to directly, in addition to NO_PROXY
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+    /// HTTP method (defaults to GET)
+    #[serde(default)]
+    pub method: Option<String>,
+    /// Request headers; values may reference environment variables
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Request body; mappings and lists are sent as JSON
+    #[serde(default)]
+    pub body: Option<serde_json::Value>,
+    /// Substrings the response body must contain
+    #[serde(default)]
+    pub expect_body_contains: Vec<String>,
+    /// Regex the response body must match
+    #[serde(default)]
+    pub expect_body_matches: Option<String>,
+    /// Expected values in a JSON response, keyed by JSON pointer (`/status`)
+    #[serde(default)]
+    pub expect_json: BTreeMap<String, serde_json::Value>,
+    /// Expected response header values
+    #[serde(default)]
+    pub expect_headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub when: Option<Condition>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum StatusPattern {
+    Code(u16),
+    Range(u16, u16),
+}
+
+impl StatusPattern {
+    pub fn matches(&self, status: u16) -> bool {
+        match *self {
+            StatusPattern::Code(code) => status == code,
+            StatusPattern::Range(low, high) => (low..=high).contains(&status),
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_lowercase();
+        if let Some(class) = value.strip_suffix("xx") {
+            let class: u16 = class.parse().ok().filter(|c| (1..=5).contains(c))?;
+            return Some(StatusPattern::Range(class * 100, class * 100 + 99));
+        }
+        if let Some((low, high)) = value.split_once('-') {
+            let (low, high) = (low.trim().parse().ok()?, high.trim().parse().ok()?);
+            return (low <= high).then_some(StatusPattern::Range(low, high));
+        }
+        value.parse().ok().map(StatusPattern::Code)
+    }
+}
+
+impl fmt::Display for StatusPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            StatusPattern::Code(code) => write!(f, "{}", code),
+            StatusPattern::Range(low, high) if low % 100 == 0 && high == low + 99 => {
+                write!(f, "{}xx", low / 100)
+            }
+            StatusPattern::Range(low, high) => write!(f, "{}-{}", low, high),
+        }
+    }
 }
 
 fn default_true() -> bool {
     true
 }
 
+fn default_min_count() -> usize {
+    1
+}
+
 fn default_false() -> bool {
     false
 }
 
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringOrNumber {
+    String(String),
+    Number(u64),
+}
+
+/// Parses an octal file mode such as "0600", "0o600" or "600".
+fn parse_mode(value: &str) -> Option<u32> {
+    let digits = value.trim();
+    let digits = digits.strip_prefix("0o").unwrap_or(digits);
+    u32::from_str_radix(digits, 8).ok().filter(|mode| *mode <= 0o7777)
+}
+
+/// Accepts modes as octal strings or integers. Unquoted numbers are read as their
+/// source text, so `permissions: 400` means 0o400 like it would for `chmod`.
+fn deserialize_mode<'de, D>(deserializer: D) -> std::result::Result<Option<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(text) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+
+    parse_mode(&text).map(Some).ok_or_else(|| {
+        serde::de::Error::custom(format!("invalid file mode '{}', expected an octal string like \"0600\"", text))
+    })
+}
+
+fn deserialize_size<'de, D>(deserializer: D) -> std::result::Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<StringOrNumber>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(StringOrNumber::Number(n)) => Ok(Some(n)),
+        Some(StringOrNumber::String(s)) => parse_size(&s).map(Some).ok_or_else(|| {
+            serde::de::Error::custom(format!("invalid size '{}', expected a value like \"20GiB\"", s))
+        }),
+    }
+}
+
+/// Like `deserialize_size`, but also accepts "unlimited" as `u64::MAX`.
+fn deserialize_limit<'de, D>(deserializer: D) -> std::result::Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<StringOrNumber>::deserialize(deserializer)? {
+        Some(StringOrNumber::String(s)) if s.eq_ignore_ascii_case("unlimited") => Ok(Some(u64::MAX)),
+        Some(StringOrNumber::String(s)) => parse_size(&s).map(Some).ok_or_else(|| {
+            serde::de::Error::custom(format!("invalid limit '{}', expected a number or \"unlimited\"", s))
+        }),
+        Some(StringOrNumber::Number(n)) => Ok(Some(n)),
+        None => Ok(None),
+    }
+}
+
+fn deserialize_duration<'de, D>(deserializer: D) -> std::result::Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<StringOrNumber>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(StringOrNumber::Number(n)) => Ok(Some(Duration::from_secs(n))),
+        Some(StringOrNumber::String(s)) => parse_duration(&s).map(Some).ok_or_else(|| {
+            serde::de::Error::custom(format!("invalid duration '{}', expected a value like \"30s\" or \"24h\"", s))
+        }),
+    }
+}
+
+fn deserialize_ports<'de, D>(deserializer: D) -> std::result::Result<Vec<PortCheck>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum PortEntry {
+        Port(u16),
+        Check(PortCheck),
+    }
+
+    Ok(Vec::<PortEntry>::deserialize(deserializer)?
+        .into_iter()
+        .map(|entry| match entry {
+            PortEntry::Port(port) => PortCheck::from(port),
+            PortEntry::Check(check) => check,
+        })
+        .collect())
+}
+
+fn deserialize_statuses<'de, D>(deserializer: D) -> std::result::Result<Vec<StatusPattern>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(StringOrNumber),
+        Many(Vec<StringOrNumber>),
+    }
+
+    let values = match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    };
+
+    values
+        .into_iter()
+        .map(|value| {
+            let text = match value {
+                StringOrNumber::String(s) => s,
+                StringOrNumber::Number(n) => n.to_string(),
+            };
+            StatusPattern::parse(&text).ok_or_else(|| {
+                serde::de::Error::custom(format!("invalid status '{}', expected a code like 200, a class like \"2xx\" or a range like \"200-299\"", text))
+            })
+        })
+        .collect()
+}
+
+fn deserialize_addresses<'de, D>(deserializer: D) -> std::result::Result<Vec<IpAddr>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|address| {
+            address.trim().parse().map_err(|_| {
+                serde::de::Error::custom(format!("invalid address '{}', expected an IP address like 127.0.0.1", address))
+            })
+        })
+        .collect()
+}
+
+fn deserialize_strings<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+fn deserialize_id<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Option::<StringOrNumber>::deserialize(deserializer)? {
+        None => None,
+        Some(StringOrNumber::String(s)) => Some(s),
+        Some(StringOrNumber::Number(n)) => Some(n.to_string()),
+    })
+}
+
 impl Config {
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
         let content = fs::read_to_string(path.as_ref())
@@ -112,4 +921,456 @@ files:
         assert_eq!(config.tools.len(), 1);
         assert_eq!(config.tools[0].name, "node");
     }
+
+    #[test]
+    fn test_parse_file_content_checks() {
+        let yaml = r#"
+version: "1"
+files:
+  - path: config/database.yml
+    contains:
+      - "adapter: postgresql"
+    not_matches:
+      - "^DEBUG=true$"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let file = &config.files[0];
+        assert_eq!(file.contains, vec!["adapter: postgresql"]);
+        assert!(file.not_contains.is_empty());
+        assert!(file.matches.is_empty());
+        assert_eq!(file.not_matches, vec!["^DEBUG=true$"]);
+        assert_eq!(file.required, FileCheck::default().required);
+    }
+
+    #[test]
+    fn test_parse_file_key_checks() {
+        let yaml = r#"
+version: "1"
+files:
+  - path: Cargo.toml
+    format: toml
+    keys:
+      - key: package.rust-version
+        semver: ">=1.70"
+      - key: package.edition
+        equals: "2021"
+      - key: workspace
+        exists: false
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let file = &config.files[0];
+        assert_eq!(file.format, Some(FileFormat::Toml));
+        assert_eq!(file.keys.len(), 3);
+        assert_eq!(file.keys[0].semver.as_deref(), Some(">=1.70"));
+        assert_eq!(file.keys[1].equals, Some(serde_json::json!("2021")));
+        assert_eq!(file.keys[2].exists, Some(false));
+    }
+
+    #[test]
+    fn test_parse_file_modes() {
+        let yaml = r#"
+version: "1"
+files:
+  - path: a
+    permissions: "0600"
+  - path: b
+    permissions: 0o644
+  - path: c
+    permissions: 600
+  - path: d
+    max_permissions: 0600
+    min_permissions: "400"
+    permissions: 400
+    owner: 1000
+    group: staff
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.files[0].permissions, Some(0o600));
+        assert_eq!(config.files[1].permissions, Some(0o644));
+        assert_eq!(config.files[2].permissions, Some(0o600));
+        assert_eq!(config.files[3].max_permissions, Some(0o600));
+        assert_eq!(config.files[3].min_permissions, Some(0o400));
+        assert_eq!(config.files[3].permissions, Some(0o400));
+        assert_eq!(config.files[3].owner.as_deref(), Some("1000"));
+        assert_eq!(config.files[3].group.as_deref(), Some("staff"));
+
+        let invalid_mode = "version: \"1\"\nfiles:\n  - path: a\n    permissions: \"0900\"\n";
+        assert!(serde_yaml::from_str::<Config>(invalid_mode).is_err());
+        let decimal_mode = "version: \"1\"\nfiles:\n  - path: a\n    permissions: 420\n";
+        let config: Config = serde_yaml::from_str(decimal_mode).unwrap();
+        assert_eq!(config.files[0].permissions, Some(0o420));
+    }
+
+    #[test]
+    fn test_parse_file_integrity_checks() {
+        let yaml = r#"
+version: "1"
+files:
+  - path: vendor/tool.bin
+    sha256: E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855
+    min_size: 1
+    max_size: 10MiB
+    max_age: 24h
+    newer_than: package-lock.json
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let file = &config.files[0];
+        assert_eq!(file.min_size, Some(1));
+        assert_eq!(file.max_size, Some(10 * 1024 * 1024));
+        assert_eq!(file.max_age, Some(Duration::from_secs(86_400)));
+        assert_eq!(file.newer_than.as_deref(), Some("package-lock.json"));
+
+        let invalid_age = "version: \"1\"\nfiles:\n  - path: a\n    max_age: soon\n";
+        assert!(serde_yaml::from_str::<Config>(invalid_age).is_err());
+    }
+
+    #[test]
+    fn test_parse_network_request() {
+        let yaml = r#"
+version: "1"
+network:
+  - url: http://localhost:8080/health
+    method: POST
+    headers:
+      Authorization: "Bearer ${API_TOKEN}"
+    body:
+      probe: true
+    expect_body_contains:
+      - ok
+    expect_json:
+      /status: up
+    expect_headers:
+      content-type: application/json
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let network = &config.network[0];
+        assert_eq!(network.method.as_deref(), Some("POST"));
+        assert_eq!(network.headers["Authorization"], "Bearer ${API_TOKEN}");
+        assert_eq!(network.body, Some(serde_json::json!({"probe": true})));
+        assert_eq!(network.expect_json["/status"], serde_json::json!("up"));
+        assert_eq!(network.expect_headers["content-type"], "application/json");
+    }
+
+    #[test]
+    fn test_parse_network_statuses() {
+        let yaml = r#"
+version: "1"
+network:
+  - url: http://localhost/a
+    status: [200, 204]
+  - url: http://localhost/b
+    status: 2xx
+  - url: http://localhost/c
+    status: ["301-302", 404]
+    follow_redirects: false
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.network[0].status, vec![StatusPattern::Code(200), StatusPattern::Code(204)]);
+        assert_eq!(config.network[1].status, vec![StatusPattern::Range(200, 299)]);
+        assert_eq!(config.network[2].status, vec![StatusPattern::Range(301, 302), StatusPattern::Code(404)]);
+        assert_eq!(config.network[2].follow_redirects, Some(false));
+        assert_eq!(config.network[1].status[0].to_string(), "2xx");
+        assert_eq!(config.network[2].status[0].to_string(), "301-302");
+        assert!(StatusPattern::Range(200, 299).matches(204));
+        assert!(!StatusPattern::Range(200, 299).matches(301));
+
+        let invalid = "version: \"1\"\nnetwork:\n  - url: http://localhost\n    status: 9xx\n";
+        assert!(serde_yaml::from_str::<Config>(invalid).is_err());
+    }
+
+    #[test]
+    fn test_parse_retries() {
+        let yaml = r#"
+version: "1"
+ports:
+  - 3000
+  - port: 5432
+    listening: true
+    wait_until_ready: 60s
+network:
+  - url: http://localhost:8080/health
+    timeout: 2s
+    retries: 3
+    retry_interval: 500ms
+    backoff: exponential
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.ports[0].port, 3000);
+        assert!(!config.ports[0].listening);
+        assert_eq!(config.ports[1].port, 5432);
+        assert!(config.ports[1].listening);
+        assert_eq!(config.ports[1].retry.wait_until_ready, Some(Duration::from_secs(60)));
+
+        let retry = &config.network[0].retry;
+        assert_eq!(retry.timeout, Some(Duration::from_secs(2)));
+        assert_eq!(retry.retries, 3);
+        assert_eq!(retry.retry_interval, Some(Duration::from_millis(500)));
+        assert_eq!(retry.backoff, Backoff::Exponential);
+    }
+
+    #[test]
+    fn test_parse_network_kinds() {
+        let yaml = r#"
+version: "1"
+network:
+  - url: tcp://localhost:5432
+  - dns: api.myapp.test
+    resolves_to: [127.0.0.1]
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.network[0].url, "tcp://localhost:5432");
+        assert_eq!(config.network[0].dns, None);
+        assert_eq!(config.network[1].url, "");
+        assert_eq!(config.network[1].dns.as_deref(), Some("api.myapp.test"));
+        assert_eq!(config.network[1].resolves_to, vec![IpAddr::from([127, 0, 0, 1])]);
+
+        let error = serde_yaml::from_str::<Config>("version: \"1\"\nnetwork:\n  - dns: api.myapp.test\n    resolves_to: [10.0.0.300]\n")
+            .unwrap_err();
+        assert!(error.to_string().contains("invalid address '10.0.0.300'"));
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        let yaml = r#"
+version: "1"
+commands:
+  - run: docker info
+  - run: git config user.email
+    stdout_matches: "@ourcompany\\.com$"
+  - run: make check 2>&1
+    shell: true
+    expect_exit: 2
+    timeout: 1m
+    cwd: ~/src/app
+    required: false
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let commands = &config.commands;
+        assert_eq!(commands[0].run, "docker info");
+        assert!(!commands[0].shell);
+        assert!(commands[0].required);
+        assert_eq!(commands[1].stdout_matches.as_deref(), Some("@ourcompany\\.com$"));
+        assert!(commands[2].shell);
+        assert_eq!(commands[2].expect_exit, Some(2));
+        assert_eq!(commands[2].timeout, Some(Duration::from_secs(60)));
+        assert_eq!(commands[2].cwd.as_deref(), Some("~/src/app"));
+        assert!(!commands[2].required);
+        assert!(CommandCheck::default().required);
+    }
+
+    #[test]
+    fn test_parse_system() {
+        let yaml = r#"
+version: "1"
+system:
+  min_free_disk: 20GiB
+  path: /var/lib/docker
+  min_memory: 8 GB
+  min_cpus: 4
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let system = config.system.unwrap();
+        assert_eq!(system.min_free_disk, Some(20 << 30));
+        assert_eq!(system.path.as_deref(), Some("/var/lib/docker"));
+        assert_eq!(system.min_memory, Some(8_000_000_000));
+        assert_eq!(system.min_cpus, Some(4));
+    }
+
+    #[test]
+    fn test_parse_os_and_conditions() {
+        let yaml = r#"
+version: "1"
+os:
+  family: linux
+  distro: [ubuntu, debian]
+  distro_version: ">=22.04"
+  kernel: ">=5.10"
+  arch: x86_64
+tools:
+  - name: brew
+    when: { os: macos }
+ports:
+  - port: 5432
+    when:
+      os: linux
+      arch: [x86_64, aarch64]
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let os = config.os.unwrap();
+        assert_eq!(os.family, vec!["linux"]);
+        assert_eq!(os.distro, vec!["ubuntu", "debian"]);
+        assert_eq!(os.distro_version.as_deref(), Some(">=22.04"));
+        assert_eq!(os.kernel.as_deref(), Some(">=5.10"));
+        assert_eq!(os.arch, vec!["x86_64"]);
+
+        assert_eq!(config.tools[0].when.as_ref().unwrap().os, vec!["macos"]);
+        let when = config.ports[0].when.as_ref().unwrap();
+        assert_eq!(when.to_string(), "os: linux, arch: x86_64 or aarch64");
+    }
+
+    #[test]
+    fn test_parse_git() {
+        let yaml = r#"
+version: "1"
+git:
+  hooks: pre-commit
+  user_email_matches: "@ourcompany\\.com$"
+  submodules: true
+  lfs: true
+  branch: [main, "release/*"]
+  clean: true
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let git = config.git.unwrap();
+        assert_eq!(git.path, None);
+        assert_eq!(git.hooks, vec!["pre-commit"]);
+        assert!(!git.user_email);
+        assert_eq!(git.user_email_matches.as_deref(), Some("@ourcompany\\.com$"));
+        assert!(git.submodules && git.lfs && git.clean);
+        assert_eq!(git.branch, vec!["main", "release/*"]);
+    }
+
+    #[test]
+    fn test_parse_docker() {
+        let yaml = r#"
+version: "1"
+docker:
+  version: ">=24.0"
+  images: [postgres:16, redis]
+  compose_file: docker-compose.yml
+  services: db
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let docker = config.docker.unwrap();
+        assert_eq!(docker.host, None);
+        assert_eq!(docker.version.as_deref(), Some(">=24.0"));
+        assert_eq!(docker.images, vec!["postgres:16", "redis"]);
+        assert_eq!(docker.compose_file.as_deref(), Some("docker-compose.yml"));
+        assert_eq!(docker.services, vec!["db"]);
+    }
+
+    #[test]
+    fn test_parse_databases() {
+        let yaml = r#"
+version: "1"
+databases:
+  - url: ${DATABASE_URL}
+    version: ">=14"
+    schema: app
+    wait_until_ready: 30s
+  - url: redis://localhost:6379/2
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let postgres = &config.databases[0];
+        assert_eq!(postgres.url, "${DATABASE_URL}");
+        assert_eq!(postgres.version.as_deref(), Some(">=14"));
+        assert_eq!(postgres.schema.as_deref(), Some("app"));
+        assert_eq!(postgres.retry.wait_until_ready, Some(Duration::from_secs(30)));
+        assert_eq!(config.databases[1].database, None);
+    }
+
+    #[test]
+    fn test_parse_libraries() {
+        let yaml = r#"
+version: "1"
+libraries:
+  - name: openssl
+    version: ">=1.1"
+    headers: openssl/ssl.h
+  - name: libpq
+    required: false
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let openssl = &config.libraries[0];
+        assert_eq!(openssl.version.as_deref(), Some(">=1.1"));
+        assert_eq!(openssl.headers, vec!["openssl/ssl.h"]);
+        assert!(openssl.required);
+        assert!(!config.libraries[1].required);
+    }
+
+    #[test]
+    fn test_parse_sysctl_and_limits() {
+        let yaml = r#"
+version: "1"
+sysctl:
+  - name: vm.max_map_count
+    min: 262144
+limits:
+  - name: nofile
+    min: 65536
+  - name: memlock
+    min: unlimited
+  - name: stack
+    max: 64MiB
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.sysctl[0].name, "vm.max_map_count");
+        assert_eq!(config.sysctl[0].min, Some(262144));
+        assert_eq!(config.limits[0].min, Some(65536));
+        assert_eq!(config.limits[1].min, Some(u64::MAX));
+        assert_eq!(config.limits[2].max, Some(64 * 1024 * 1024));
+    }
+
+    #[test]
+    fn test_parse_processes_and_services() {
+        let yaml = r#"
+version: "1"
+processes:
+  - name: ssh-agent
+    user: alice
+  - matches: "postgres: .* writer"
+    min_count: 2
+services:
+  - name: docker
+    enabled: true
+  - name: syncthing.service
+    user: true
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.processes[0].name.as_deref(), Some("ssh-agent"));
+        assert_eq!(config.processes[0].min_count, 1);
+        assert_eq!(config.processes[1].min_count, 2);
+        assert!(config.services[0].enabled && !config.services[0].user);
+        assert!(config.services[1].user);
+    }
+
+    #[test]
+    fn test_parse_python() {
+        let yaml = r#"
+version: "1"
+python:
+  venv: .venv
+  version: ">=3.11"
+  requirements: [requirements.txt, requirements-dev.txt]
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let python = config.python.unwrap();
+        assert_eq!(python.venv.as_deref(), Some(".venv"));
+        assert_eq!(python.version.as_deref(), Some(">=3.11"));
+        assert_eq!(python.requirements, vec!["requirements.txt", "requirements-dev.txt"]);
+    }
+
+    #[test]
+    fn test_parse_node() {
+        let yaml = r#"
+version: "1"
+node:
+  path: frontend
+  engines: false
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let node = config.node.unwrap();
+        assert_eq!(node.path.as_deref(), Some("frontend"));
+        assert!(node.dependencies && !node.engines && node.package_manager);
+    }
+
+    #[test]
+    fn test_parse_examples() {
+        let parse = |example: &str| serde_yaml::from_str::<Config>(example).unwrap();
+        parse(include_str!("../examples/django-project.yaml"));
+        let rails = parse(include_str!("../examples/rails-project.yaml"));
+        assert_eq!(rails.ruby.unwrap().path.as_deref(), Some("."));
+        assert!(parse(include_str!("../examples/go-project.yaml")).go.is_some());
+        assert!(parse(include_str!("../examples/rust-project.yaml")).cargo.is_some());
+    }
 }
@@ -0,0 +1,146 @@
+use std::env;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ExpandError {
+    #[error("unresolved variable ${{{0}}}")]
+    UnresolvedVariable(String),
+    #[error("unterminated variable reference in '{0}'")]
+    Unterminated(String),
+    #[error("home directory could not be determined")]
+    NoHome,
+}
+
+impl ExpandError {
+    pub fn suggestion(&self) -> String {
+        match self {
+            ExpandError::UnresolvedVariable(name) => format!(
+                "Set {} or provide a default with ${{{}:-value}}",
+                name, name
+            ),
+            ExpandError::Unterminated(_) => "Close the variable reference with '}'".to_string(),
+            ExpandError::NoHome => "Set the HOME environment variable".to_string(),
+        }
+    }
+}
+
+/// Expands a leading `~` and any environment variable references in a path.
+pub fn expand_path(input: &str) -> Result<String, ExpandError> {
+    let expanded = expand_vars(input)?;
+    let rest = match expanded.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') || rest.starts_with('\\') => rest,
+        _ => return Ok(expanded),
+    };
+    Ok(format!("{}{}", home_dir().ok_or(ExpandError::NoHome)?, rest))
+}
+
+/// Expands `$VAR`, `${VAR}` and `${VAR:-default}` references; `$$` is a literal `$`.
+/// Only the braced form is an error when the variable is unset.
+pub fn expand_vars(input: &str) -> Result<String, ExpandError> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(pos) = rest.find('$') {
+        output.push_str(&rest[..pos]);
+        rest = &rest[pos + 1..];
+
+        if let Some(after) = rest.strip_prefix('$') {
+            output.push('$');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('{') {
+            let end = find_closing_brace(after).ok_or_else(|| ExpandError::Unterminated(input.to_string()))?;
+            let reference = &after[..end];
+            rest = &after[end + 1..];
+            let (name, default) = match reference.split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (reference, None),
+            };
+            // Like the shell, `:-` also substitutes the default for empty values
+            match (env::var(name), default) {
+                (Ok(value), Some(_)) if !value.is_empty() => output.push_str(&value),
+                (Ok(value), None) => output.push_str(&value),
+                (_, Some(default)) => output.push_str(&expand_vars(default)?),
+                (Err(_), None) => return Err(ExpandError::UnresolvedVariable(name.to_string())),
+            }
+        } else {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            if len == 0 {
+                // A lone `$` is kept as-is
+                output.push('$');
+                continue;
+            }
+            let name = &rest[..len];
+            rest = &rest[len..];
+            // An unset bare `$NAME` is kept as-is, since paths and URLs may contain a literal `$`
+            match env::var(name) {
+                Ok(value) => output.push_str(&value),
+                Err(_) => {
+                    output.push('$');
+                    output.push_str(name);
+                }
+            }
+        }
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn find_closing_brace(input: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in input.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn home_dir() -> Option<String> {
+    env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .ok()
+        .filter(|home| !home.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_vars() {
+        env::set_var("ENVCHECK_EXPAND_HOST", "localhost");
+        env::remove_var("ENVCHECK_EXPAND_MISSING");
+
+        assert_eq!(expand_vars("http://$ENVCHECK_EXPAND_HOST:3000").unwrap(), "http://localhost:3000");
+        assert_eq!(expand_vars("${ENVCHECK_EXPAND_HOST}/api").unwrap(), "localhost/api");
+        assert_eq!(expand_vars("${ENVCHECK_EXPAND_MISSING:-127.0.0.1}").unwrap(), "127.0.0.1");
+        assert_eq!(
+            expand_vars("${ENVCHECK_EXPAND_MISSING:-${ENVCHECK_EXPAND_HOST}}").unwrap(),
+            "localhost"
+        );
+        assert_eq!(expand_vars("price: $$5 or $").unwrap(), "price: $5 or $");
+        assert_eq!(expand_vars("$ENVCHECK_EXPAND_MISSING/x").unwrap(), "$ENVCHECK_EXPAND_MISSING/x");
+        assert_eq!(expand_vars("price$list.txt").unwrap(), "price$list.txt");
+        assert_eq!(
+            expand_vars("${ENVCHECK_EXPAND_MISSING}/x"),
+            Err(ExpandError::UnresolvedVariable("ENVCHECK_EXPAND_MISSING".to_string()))
+        );
+        assert!(matches!(expand_vars("${ENVCHECK_EXPAND_HOST"), Err(ExpandError::Unterminated(_))));
+    }
+
+    #[test]
+    fn test_expand_path() {
+        let home = home_dir().unwrap();
+        assert_eq!(expand_path("~/.aws/credentials").unwrap(), format!("{}/.aws/credentials", home));
+        assert_eq!(expand_path("~").unwrap(), home);
+        assert_eq!(expand_path("$HOME/.kube/config").unwrap(), format!("{}/.kube/config", home));
+        assert_eq!(expand_path("~other/file").unwrap(), "~other/file");
+        assert_eq!(expand_path("config/~backup").unwrap(), "config/~backup");
+    }
+}
@@ -1,6 +1,8 @@
 pub mod config;
+pub mod expand;
 pub mod validators;
 pub mod reporter;
+pub mod units;
 
 pub use config::Config;
 pub use validators::{ValidationResult, ValidationStatus, Validator};
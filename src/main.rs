@@ -3,8 +3,10 @@ use anyhow::Result;
 use std::process;
 
 mod config;
+mod expand;
 mod validators;
 mod reporter;
+mod units;
 
 use config::Config;
 use reporter::Reporter;
@@ -29,6 +31,10 @@ struct Cli {
     /// Output in JSON format
     #[arg(long)]
     json: bool,
+
+    /// Skip network checks (for air-gapped machines)
+    #[arg(long)]
+    offline: bool,
 }
 
 #[derive(clap::Subcommand, Debug)]
@@ -94,7 +100,8 @@ fn run_validation(args: &Cli) -> Result<()> {
     }
 
     // Run validations
-    let results = validators::run_all_validations(&config)?;
+    let options = validators::RunOptions { offline: args.offline };
+    let results = validators::run_all_validations_with(&config, &options)?;
 
     // Report results
     let reporter = Reporter::new(results);
@@ -25,6 +25,7 @@ impl Reporter {
 
         let mut error_count = 0;
         let mut warning_count = 0;
+        let mut skipped_count = 0;
         for result in &self.results {
             match result.status {
                 ValidationStatus::Success => {
@@ -44,6 +45,10 @@ impl Reporter {
                         println!("  {}", suggestion.dimmed());
                     }
                 }
+                ValidationStatus::Skipped => {
+                    skipped_count += 1;
+                    println!("{} {}", "-".dimmed().bold(), result.message.dimmed());
+                }
             }
         }
 
@@ -64,6 +69,10 @@ impl Reporter {
         } else {
             println!("{} All checks passed!", "✓".green().bold());
         }
+
+        if skipped_count > 0 {
+            println!("{} check(s) skipped.", skipped_count);
+        }
         
         println!();
     }
@@ -75,6 +84,7 @@ impl Reporter {
                 "errors": self.results.iter().filter(|r| matches!(r.status, ValidationStatus::Error)).count(),
                 "warnings": self.results.iter().filter(|r| matches!(r.status, ValidationStatus::Warning)).count(),
                 "successes": self.results.iter().filter(|r| matches!(r.status, ValidationStatus::Success)).count(),
+                "skipped": self.results.iter().filter(|r| matches!(r.status, ValidationStatus::Skipped)).count(),
             },
             "passed": !self.has_errors()
         });
@@ -0,0 +1,323 @@
+use crate::config::CargoCheck;
+use crate::expand::expand_path;
+use crate::validators::{ValidationResult, Validator};
+use anyhow::Result;
+use semver::{Version, VersionReq};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+/// How many out-of-date dependencies are listed before "and N more".
+const MAX_LISTED: usize = 5;
+const DEPENDENCY_TABLES: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// A dependency from Cargo.toml, by package name (after `package = "..."` renames).
+#[derive(Debug, Clone, PartialEq)]
+struct Dependency {
+    name: String,
+    requirement: Option<String>,
+}
+
+pub struct CargoValidator {
+    check: CargoCheck,
+}
+
+impl CargoValidator {
+    pub fn new(check: CargoCheck) -> Self {
+        Self { check }
+    }
+
+    fn check_in(&self, dir: &Path) -> Vec<ValidationResult> {
+        let mut results = Vec::new();
+        // Resolve relative paths like `.` so the workspace root search below can walk up
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        let dir = dir.as_path();
+        let manifest = match read_manifest(dir) {
+            Ok(manifest) => manifest,
+            Err(message) => {
+                results.push(ValidationResult::error(
+                    message,
+                    Some("Set cargo.path to the directory of your Rust package".to_string()),
+                ));
+                return results;
+            }
+        };
+
+        // Members of a workspace share the lockfile and [workspace.dependencies] of its root
+        let root = dir
+            .ancestors()
+            .find(|ancestor| {
+                read_manifest(ancestor).is_ok_and(|manifest| manifest.get("workspace").is_some())
+            })
+            .unwrap_or(dir)
+            .to_path_buf();
+        let root_manifest = if root == dir { Some(manifest.clone()) } else { read_manifest(&root).ok() };
+        let lock_path = root.join("Cargo.lock");
+        let lock = match fs::read_to_string(&lock_path) {
+            Ok(contents) => match parse_lockfile(&contents) {
+                Ok(lock) => lock,
+                Err(e) => {
+                    results.push(ValidationResult::error(
+                        format!("Cannot parse {}: {}", lock_path.display(), e),
+                        Some("Regenerate it with `cargo generate-lockfile`".to_string()),
+                    ));
+                    return results;
+                }
+            },
+            Err(_) => {
+                results.push(ValidationResult::error(
+                    format!("Cargo.lock is missing in {}", root.display()),
+                    Some("Run `cargo generate-lockfile` and commit Cargo.lock".to_string()),
+                ));
+                return results;
+            }
+        };
+
+        let workspace_deps = root_manifest
+            .as_ref()
+            .and_then(|manifest| manifest.get("workspace"))
+            .and_then(|workspace| workspace.get("dependencies"));
+        // A virtual manifest has no [package] of its own, so check its members instead
+        let packages: Vec<Value> = if manifest.get("package").is_some() {
+            vec![manifest]
+        } else {
+            workspace_members(dir, &manifest)
+                .iter()
+                .filter_map(|member| read_manifest(member).ok())
+                .collect()
+        };
+
+        let mut stale = Vec::new();
+        let mut count = 0;
+        for package in &packages {
+            if let Some((name, version)) = package_version(package) {
+                if !lock.get(&name).is_some_and(|locked| locked.contains(&version)) {
+                    stale.push(format!("{} {} (locked {})", name, version, locked_versions(&lock, &name)));
+                }
+            }
+            for dependency in dependencies(package, workspace_deps) {
+                count += 1;
+                let Some(locked) = lock.get(&dependency.name) else {
+                    stale.push(format!("{} (not in Cargo.lock)", dependency.name));
+                    continue;
+                };
+                let Some(requirement) = dependency.requirement.as_deref() else {
+                    continue;
+                };
+                let Ok(req) = VersionReq::parse(requirement) else {
+                    continue;
+                };
+                let satisfied = locked
+                    .iter()
+                    .filter_map(|version| Version::parse(version).ok())
+                    .any(|version| req.matches(&version));
+                if !satisfied {
+                    stale.push(format!(
+                        "{} {} (locked {})",
+                        dependency.name,
+                        requirement,
+                        locked_versions(&lock, &dependency.name)
+                    ));
+                }
+            }
+        }
+        stale.dedup();
+
+        if stale.is_empty() {
+            results.push(ValidationResult::success(format!(
+                "Cargo.lock is consistent with Cargo.toml ({} dependencies)",
+                count
+            )));
+        } else {
+            let more = match stale.len().saturating_sub(MAX_LISTED) {
+                0 => String::new(),
+                n => format!(" (and {} more)", n),
+            };
+            stale.truncate(MAX_LISTED);
+            results.push(ValidationResult::error(
+                format!("Cargo.lock is out of date with Cargo.toml: {}{}", stale.join("; "), more),
+                Some("Run `cargo check` to update Cargo.lock and commit it".to_string()),
+            ));
+        }
+        results
+    }
+}
+
+impl Validator for CargoValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        match expand_path(self.check.path.as_deref().unwrap_or(".")) {
+            Ok(dir) => Ok(self.check_in(Path::new(&dir))),
+            Err(e) => Ok(vec![ValidationResult::error(
+                format!("Invalid cargo path in config: {}", e),
+                Some(e.suggestion()),
+            )]),
+        }
+    }
+}
+
+fn read_manifest(dir: &Path) -> std::result::Result<Value, String> {
+    let path = dir.join("Cargo.toml");
+    let contents = fs::read_to_string(&path).map_err(|_| format!("No Cargo.toml in {}", dir.display()))?;
+    toml::from_str(&contents).map_err(|e| format!("Cannot parse {}: {}", path.display(), e))
+}
+
+/// Locked versions by package name; a name can be locked at several versions.
+fn parse_lockfile(contents: &str) -> std::result::Result<HashMap<String, Vec<String>>, toml::de::Error> {
+    let lock: Value = toml::from_str(contents)?;
+    let mut packages: HashMap<String, Vec<String>> = HashMap::new();
+    for package in lock.get("package").and_then(Value::as_array).into_iter().flatten() {
+        if let (Some(name), Some(version)) = (
+            package.get("name").and_then(Value::as_str),
+            package.get("version").and_then(Value::as_str),
+        ) {
+            packages.entry(name.to_string()).or_default().push(version.to_string());
+        }
+    }
+    Ok(packages)
+}
+
+fn locked_versions(lock: &HashMap<String, Vec<String>>, name: &str) -> String {
+    lock.get(name).map(|versions| versions.join(", ")).unwrap_or_else(|| "none".to_string())
+}
+
+/// The package's own name and version, unless the version is inherited from the workspace.
+fn package_version(manifest: &Value) -> Option<(String, String)> {
+    let package = manifest.get("package")?;
+    Some((
+        package.get("name")?.as_str()?.to_string(),
+        package.get("version")?.as_str()?.to_string(),
+    ))
+}
+
+/// Directories matching the `workspace.members` globs that contain a Cargo.toml.
+fn workspace_members(dir: &Path, manifest: &Value) -> Vec<PathBuf> {
+    let patterns = manifest
+        .get("workspace")
+        .and_then(|workspace| workspace.get("members"))
+        .and_then(Value::as_array);
+    patterns
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .filter_map(|pattern| glob::glob(&dir.join(pattern).display().to_string()).ok())
+        .flat_map(|paths| paths.flatten())
+        .filter(|path| path.join("Cargo.toml").is_file())
+        .collect()
+}
+
+/// Every dependency of a package, including dev, build and target-specific ones.
+fn dependencies(manifest: &Value, workspace_deps: Option<&Value>) -> Vec<Dependency> {
+    let mut tables: Vec<&Value> = DEPENDENCY_TABLES.iter().filter_map(|table| manifest.get(*table)).collect();
+    if let Some(targets) = manifest.get("target").and_then(Value::as_table) {
+        for target in targets.values() {
+            tables.extend(DEPENDENCY_TABLES.iter().filter_map(|table| target.get(*table)));
+        }
+    }
+
+    let mut dependencies = Vec::new();
+    for (key, spec) in tables.iter().filter_map(|table| table.as_table()).flatten() {
+        // `foo = { workspace = true }` takes its package and version from the workspace root
+        let spec = match spec.get("workspace").and_then(Value::as_bool) {
+            Some(true) => match workspace_deps.and_then(|deps| deps.get(key)) {
+                Some(spec) => spec,
+                None => continue,
+            },
+            _ => spec,
+        };
+        let (name, requirement) = match spec {
+            Value::String(version) => (key.as_str(), Some(version.clone())),
+            spec => (
+                spec.get("package").and_then(Value::as_str).unwrap_or(key),
+                spec.get("version").and_then(Value::as_str).map(str::to_string),
+            ),
+        };
+        dependencies.push(Dependency {
+            name: name.to_string(),
+            requirement,
+        });
+    }
+    dependencies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validators::ValidationStatus;
+
+    fn lock(packages: &[(&str, &str)]) -> String {
+        packages
+            .iter()
+            .map(|(name, version)| format!("[[package]]\nname = \"{}\"\nversion = \"{}\"\n\n", name, version))
+            .collect()
+    }
+
+    #[test]
+    fn test_dependencies() {
+        let manifest: Value = toml::from_str(
+            r#"
+[package]
+name = "api"
+version = "0.2.0"
+
+[dependencies]
+serde = { workspace = true }
+yaml = { package = "serde_yaml", version = "0.9" }
+shared = { path = "../shared" }
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+"#,
+        )
+        .unwrap();
+        let workspace: Value = toml::from_str("serde = { version = \"1.0.190\", features = [\"derive\"] }").unwrap();
+        let mut names: Vec<(String, Option<String>)> = dependencies(&manifest, Some(&workspace))
+            .into_iter()
+            .map(|dependency| (dependency.name, dependency.requirement))
+            .collect();
+        names.sort();
+        let expected = [("libc", Some("0.2")), ("serde", Some("1.0.190")), ("serde_yaml", Some("0.9")), ("shared", None)];
+        let expected: Vec<(String, Option<String>)> = expected
+            .iter()
+            .map(|(name, version)| (name.to_string(), version.map(str::to_string)))
+            .collect();
+        assert_eq!(names, expected);
+        assert_eq!(package_version(&manifest), Some(("api".to_string(), "0.2.0".to_string())));
+    }
+
+    #[test]
+    fn test_lock_consistency() {
+        let root = tempfile::tempdir().unwrap();
+        fs::write(
+            root.path().join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.dependencies]\nserde = \"1.0.190\"\n",
+        )
+        .unwrap();
+        let member = root.path().join("crates/api");
+        fs::create_dir_all(&member).unwrap();
+        fs::write(
+            member.join("Cargo.toml"),
+            "[package]\nname = \"api\"\nversion = \"0.2.0\"\n\n[dependencies]\nserde = { workspace = true }\nanyhow = \"1\"\n",
+        )
+        .unwrap();
+
+        let validator = CargoValidator::new(CargoCheck::default());
+        let results = validator.check_in(&member);
+        assert_eq!(results[0].message, format!("Cargo.lock is missing in {}", root.path().display()));
+
+        fs::write(root.path().join("Cargo.lock"), lock(&[("api", "0.1.0"), ("serde", "1.0.188")])).unwrap();
+        let results = validator.check_in(&member);
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+        assert_eq!(
+            results[0].message,
+            "Cargo.lock is out of date with Cargo.toml: api 0.2.0 (locked 0.1.0); \
+             anyhow (not in Cargo.lock); serde 1.0.190 (locked 1.0.188)"
+        );
+
+        let locked = lock(&[("api", "0.2.0"), ("serde", "1.0.193"), ("anyhow", "1.0.79")]);
+        fs::write(root.path().join("Cargo.lock"), locked).unwrap();
+        let results = validator.check_in(root.path());
+        assert!(matches!(results[0].status, ValidationStatus::Success));
+        assert_eq!(results[0].message, "Cargo.lock is consistent with Cargo.toml (2 dependencies)");
+    }
+}
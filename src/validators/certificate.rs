@@ -0,0 +1,187 @@
+use crate::validators::ValidationResult;
+use std::net::IpAddr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::{parse_x509_certificate, X509Certificate};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Reports expiry, hostname coverage and issuer of a DER encoded peer certificate.
+///
+/// Certificates expiring within `warn_days` produce a warning, expired ones an error.
+pub fn inspect_certificate(der: &[u8], host: &str, warn_days: u32, now: SystemTime) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    let cert = match parse_x509_certificate(der) {
+        Ok((_, cert)) => cert,
+        Err(e) => {
+            results.push(ValidationResult::error(
+                format!("Failed to parse the certificate presented by {}", host),
+                Some(format!("Error: {}", e)),
+            ));
+            return results;
+        }
+    };
+
+    let issuer = issuer_name(&cert);
+    let now = now
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    let not_after = cert.validity().not_after.timestamp();
+    let not_before = cert.validity().not_before.timestamp();
+    let days_left = (not_after - now).div_euclid(SECONDS_PER_DAY);
+
+    if now > not_after {
+        results.push(ValidationResult::error(
+            format!(
+                "Certificate for {} expired {} day(s) ago (issuer: {})",
+                host,
+                (now - not_after) / SECONDS_PER_DAY,
+                issuer
+            ),
+            Some(format!("Renew the certificate for {}", host)),
+        ));
+    } else if now < not_before {
+        results.push(ValidationResult::error(
+            format!("Certificate for {} is not valid yet (issuer: {})", host, issuer),
+            Some(format!("Check the system clock or the certificate for {}", host)),
+        ));
+    } else if days_left < i64::from(warn_days) {
+        results.push(ValidationResult::warning(
+            format!(
+                "Certificate for {} expires in {} day(s) (issuer: {})",
+                host, days_left, issuer
+            ),
+            Some(format!("Renew the certificate for {} before it expires", host)),
+        ));
+    } else {
+        results.push(ValidationResult::success(format!(
+            "Certificate for {} expires in {} day(s) (issuer: {})",
+            host, days_left, issuer
+        )));
+    }
+
+    let names = certificate_names(&cert);
+    if !names.iter().any(|name| name_matches(name, host)) {
+        results.push(ValidationResult::error(
+            format!("Certificate for {} does not cover that hostname", host),
+            Some(format!("Certificate is valid for: {}", names.join(", "))),
+        ));
+    }
+
+    results
+}
+
+fn issuer_name(cert: &X509Certificate) -> String {
+    cert.issuer()
+        .iter_common_name()
+        .next()
+        .and_then(|cn| cn.as_str().ok())
+        .map(|cn| cn.to_string())
+        .unwrap_or_else(|| cert.issuer().to_string())
+}
+
+/// Returns the subject alternative names, or the common name if there are none.
+fn certificate_names(cert: &X509Certificate) -> Vec<String> {
+    let mut names = Vec::new();
+    if let Ok(Some(san)) = cert.subject_alternative_name() {
+        for name in &san.value.general_names {
+            match name {
+                GeneralName::DNSName(dns) => names.push(dns.to_string()),
+                GeneralName::IPAddress(bytes) => {
+                    let ip = match bytes.len() {
+                        4 => <[u8; 4]>::try_from(*bytes).ok().map(IpAddr::from),
+                        16 => <[u8; 16]>::try_from(*bytes).ok().map(IpAddr::from),
+                        _ => None,
+                    };
+                    names.extend(ip.map(|ip| ip.to_string()));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if names.is_empty() {
+        names.extend(
+            cert.subject()
+                .iter_common_name()
+                .filter_map(|cn| cn.as_str().ok())
+                .map(|cn| cn.to_string()),
+        );
+    }
+    names
+}
+
+/// Matches a hostname against a certificate name, where `*` covers exactly one label.
+fn name_matches(name: &str, host: &str) -> bool {
+    let name = name.to_lowercase();
+    let host = host.trim_start_matches('[').trim_end_matches(']').to_lowercase();
+    match name.strip_prefix("*.") {
+        Some(suffix) => host
+            .split_once('.')
+            .is_some_and(|(label, rest)| !label.is_empty() && rest == suffix),
+        None => name == host,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validators::ValidationStatus;
+    use std::time::Duration;
+    use x509_parser::pem::parse_x509_pem;
+
+    // Self-signed for localhost, *.dev.test and 127.0.0.1, valid 2020-01-01 to 2030-01-01
+    const CERT_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIB1DCCAXugAwIBAgIUL1Q4KJJQASuPck957RFoVd/aD8YwCgYIKoZIzj0EAwIw
+LDESMBAGA1UEAwwJbG9jYWxob3N0MRYwFAYDVQQKDA1lbnZjaGVjayB0ZXN0MB4X
+DTIwMDEwMTAwMDAwMFoXDTMwMDEwMTAwMDAwMFowLDESMBAGA1UEAwwJbG9jYWxo
+b3N0MRYwFAYDVQQKDA1lbnZjaGVjayB0ZXN0MFkwEwYHKoZIzj0CAQYIKoZIzj0D
+AQcDQgAEO6O4jG468LxDuc9CL23tmYvQdOqXiqYrg0pxONSVbEaxgtkt+XHK9CsB
+9OnQqXta6Nk63DmI6OnmbS04zzn4kqN7MHkwHQYDVR0OBBYEFClN4ct6T3DjZSet
+1dTXIqroZpVUMB8GA1UdIwQYMBaAFClN4ct6T3DjZSet1dTXIqroZpVUMA8GA1Ud
+EwEB/wQFMAMBAf8wJgYDVR0RBB8wHYIJbG9jYWxob3N0ggoqLmRldi50ZXN0hwR/
+AAABMAoGCCqGSM49BAMCA0cAMEQCIA74t6O459yh4UI8RnFa7HkxvYWHfZjRszgK
+bShmAyrtAiAR0OKKxEBMmgAZ8ycqTl6hviDwD4tyUVyXWrJ/zXF7jQ==
+-----END CERTIFICATE-----
+";
+    // 2030-01-01T00:00:00Z
+    const NOT_AFTER: u64 = 1_893_456_000;
+
+    fn inspect(host: &str, now: u64) -> Vec<ValidationResult> {
+        let (_, pem) = parse_x509_pem(CERT_PEM.as_bytes()).unwrap();
+        inspect_certificate(&pem.contents, host, 14, UNIX_EPOCH + Duration::from_secs(now))
+    }
+
+    #[test]
+    fn test_certificate_expiry() {
+        let results = inspect("localhost", NOT_AFTER - 100 * 86_400);
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0].status, ValidationStatus::Success));
+        assert_eq!(results[0].message, "Certificate for localhost expires in 100 day(s) (issuer: localhost)");
+
+        let results = inspect("localhost", NOT_AFTER - 7 * 86_400);
+        assert!(matches!(results[0].status, ValidationStatus::Warning));
+        assert_eq!(results[0].message, "Certificate for localhost expires in 7 day(s) (issuer: localhost)");
+
+        let results = inspect("localhost", NOT_AFTER + 3 * 86_400);
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+        assert_eq!(results[0].message, "Certificate for localhost expired 3 day(s) ago (issuer: localhost)");
+    }
+
+    #[test]
+    fn test_certificate_hostnames() {
+        let now = NOT_AFTER - 100 * 86_400;
+        assert_eq!(inspect("api.dev.test", now).len(), 1);
+        assert_eq!(inspect("127.0.0.1", now).len(), 1);
+
+        let results = inspect("a.b.dev.test", now);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].message, "Certificate for a.b.dev.test does not cover that hostname");
+        assert_eq!(
+            results[1].suggestion.as_deref(),
+            Some("Certificate is valid for: localhost, *.dev.test, 127.0.0.1")
+        );
+    }
+}
@@ -0,0 +1,377 @@
+use crate::config::CommandCheck;
+use crate::expand::expand_path;
+use crate::units::format_duration;
+use crate::validators::{ValidationResult, Validator};
+use anyhow::Result;
+use std::io::Read;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// How long to keep reading output after the command exits. Background processes
+/// started by the command can hold the pipes open long after it is done.
+const PIPE_GRACE: Duration = Duration::from_millis(200);
+
+pub struct CommandValidator {
+    check: CommandCheck,
+}
+
+struct Output {
+    status: ExitStatus,
+    stdout: String,
+    stderr: String,
+}
+
+enum RunError {
+    Spawn(String),
+    TimedOut,
+}
+
+impl CommandValidator {
+    pub fn new(check: CommandCheck) -> Self {
+        Self { check }
+    }
+
+    fn build_command(&self) -> std::result::Result<Command, String> {
+        let mut command = if self.check.shell {
+            let mut command = if cfg!(windows) {
+                Command::new("cmd")
+            } else {
+                Command::new("sh")
+            };
+            command
+                .arg(if cfg!(windows) { "/C" } else { "-c" })
+                .arg(&self.check.run);
+            command
+        } else {
+            let args = split_args(&self.check.run)?;
+            let (program, args) = args.split_first().ok_or("The command is empty")?;
+            let mut command = Command::new(program);
+            command.args(args);
+            command
+        };
+
+        if let Some(cwd) = &self.check.cwd {
+            let cwd = expand_path(cwd).map_err(|e| format!("Invalid cwd: {}", e))?;
+            command.current_dir(cwd);
+        }
+        Ok(command)
+    }
+
+    /// Runs the command, killing it and anything it started if it outlives the timeout.
+    fn run(&self, mut command: Command) -> std::result::Result<Output, RunError> {
+        command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+        // Run in a process group of its own so a timeout also reaches backgrounded children
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        let mut child = command.spawn().map_err(|e| RunError::Spawn(e.to_string()))?;
+
+        // Drain both pipes on their own threads so a chatty command can't block on a full pipe
+        let stdout = child.stdout.take().map(read_chunks);
+        let stderr = child.stderr.take().map(read_chunks);
+
+        let deadline = Instant::now() + self.check.timeout.unwrap_or(DEFAULT_TIMEOUT);
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if Instant::now() >= deadline => {
+                    kill(&mut child);
+                    return Err(RunError::TimedOut);
+                }
+                Ok(None) => thread::sleep(POLL_INTERVAL),
+                Err(e) => return Err(RunError::Spawn(e.to_string())),
+            }
+        };
+
+        let until = Instant::now() + PIPE_GRACE;
+        Ok(Output {
+            status,
+            stdout: collect(stdout, until),
+            stderr: collect(stderr, until),
+        })
+    }
+
+    fn failure(&self, message: String, suggestion: Option<String>) -> ValidationResult {
+        if self.check.required {
+            ValidationResult::error(message, suggestion)
+        } else {
+            ValidationResult::warning(message, suggestion)
+        }
+    }
+
+    fn check_output(&self, stream: &str, output: &str, pattern: &str, results: &mut Vec<ValidationResult>) {
+        // Ignore the trailing newline so `$` anchors at the end of the last line
+        let output = output.trim_end_matches(['\r', '\n']);
+        let is_match = match regex::Regex::new(pattern) {
+            Ok(re) => re.is_match(output),
+            Err(_) => output.contains(pattern), // Fallback to substring if regex is invalid
+        };
+        if !is_match {
+            results.push(self.failure(
+                format!("{} of `{}` does not match '{}'", stream, self.check.run, pattern),
+                Some(format!("Got: {}", summarize(output))),
+            ));
+        }
+    }
+}
+
+impl Validator for CommandValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        let mut results = Vec::new();
+        let run = &self.check.run;
+
+        let command = match self.build_command() {
+            Ok(command) => command,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Invalid command `{}` in config", run),
+                    Some(e),
+                ));
+                return Ok(results);
+            }
+        };
+
+        let output = match self.run(command) {
+            Ok(output) => output,
+            Err(RunError::Spawn(e)) => {
+                results.push(self.failure(format!("Failed to run `{}`", run), Some(format!("Error: {}", e))));
+                return Ok(results);
+            }
+            Err(RunError::TimedOut) => {
+                let timeout = self.check.timeout.unwrap_or(DEFAULT_TIMEOUT);
+                results.push(self.failure(
+                    format!("`{}` timed out after {}", run, format_duration(timeout)),
+                    Some("Increase the timeout or check why the command hangs".to_string()),
+                ));
+                return Ok(results);
+            }
+        };
+
+        let expected = self.check.expect_exit.unwrap_or(0);
+        match output.status.code() {
+            Some(code) if code == expected => {}
+            code => {
+                let exited = code
+                    .map(|code| format!("exited with code {}", code))
+                    .unwrap_or_else(|| "was terminated by a signal".to_string());
+                let stderr = summarize(&output.stderr);
+                results.push(self.failure(
+                    format!("`{}` {}, expected {}", run, exited, expected),
+                    (!stderr.is_empty()).then_some(stderr),
+                ));
+            }
+        }
+
+        if let Some(pattern) = &self.check.stdout_matches {
+            self.check_output("Output", &output.stdout, pattern, &mut results);
+        }
+        if let Some(pattern) = &self.check.stderr_matches {
+            self.check_output("Error output", &output.stderr, pattern, &mut results);
+        }
+
+        if results.is_empty() {
+            results.push(ValidationResult::success(format!("`{}` passed", run)));
+        }
+        Ok(results)
+    }
+}
+
+/// Kills the command's whole process group (just the command on Windows) and reaps it.
+fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    // SAFETY: kill only sends a signal; the group id is the child's pid, set by process_group(0)
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Forwards everything read from a pipe over a channel, which closes at end of file.
+fn read_chunks(mut pipe: impl Read + Send + 'static) -> Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut chunk = [0; 4096];
+        while let Ok(len @ 1..) = pipe.read(&mut chunk) {
+            if sender.send(chunk[..len].to_vec()).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// Collects output until the pipe closes or `until`, whichever comes first.
+fn collect(receiver: Option<Receiver<Vec<u8>>>, until: Instant) -> String {
+    let mut output = Vec::new();
+    if let Some(receiver) = receiver {
+        while let Ok(chunk) = receiver.recv_timeout(until.saturating_duration_since(Instant::now())) {
+            output.extend(chunk);
+        }
+    }
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+/// Returns the first non-empty line of command output for use in a message.
+fn summarize(output: &str) -> String {
+    output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Splits a command line into arguments, honoring single and double quotes
+/// and backslash escapes the way a POSIX shell would.
+fn split_args(line: &str) -> std::result::Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("Unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err("Unterminated double quote".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("Unterminated double quote".to_string()),
+                    }
+                }
+            }
+            '\\' => {
+                in_arg = true;
+                current.extend(chars.next());
+            }
+            c if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                in_arg = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validators::ValidationStatus;
+
+    fn validate(check: CommandCheck) -> Vec<ValidationResult> {
+        CommandValidator::new(check).validate().unwrap()
+    }
+
+    #[test]
+    fn test_split_args() {
+        assert_eq!(split_args("git config user.email").unwrap(), vec!["git", "config", "user.email"]);
+        assert_eq!(
+            split_args(r#"echo 'a b' "c \"d\"" e\ f """#).unwrap(),
+            vec!["echo", "a b", "c \"d\"", "e f", ""]
+        );
+        assert!(split_args("echo 'oops").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exit_code_and_output() {
+        let results = validate(CommandCheck {
+            run: "echo dev@ourcompany.com".to_string(),
+            stdout_matches: Some(r"@ourcompany\.com$".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0].status, ValidationStatus::Success));
+        assert_eq!(results[0].message, "`echo dev@ourcompany.com` passed");
+
+        let results = validate(CommandCheck {
+            run: "echo oops >&2; exit 3".to_string(),
+            shell: true,
+            stderr_matches: Some("fine".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].message, "`echo oops >&2; exit 3` exited with code 3, expected 0");
+        assert_eq!(results[0].suggestion.as_deref(), Some("oops"));
+        assert_eq!(results[1].message, "Error output of `echo oops >&2; exit 3` does not match 'fine'");
+
+        let results = validate(CommandCheck {
+            run: "exit 3".to_string(),
+            shell: true,
+            expect_exit: Some(3),
+            ..Default::default()
+        });
+        assert!(matches!(results[0].status, ValidationStatus::Success));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timeout_and_missing_command() {
+        let results = validate(CommandCheck {
+            run: "sleep 5".to_string(),
+            timeout: Some(Duration::from_millis(50)),
+            required: false,
+            ..Default::default()
+        });
+        assert!(matches!(results[0].status, ValidationStatus::Warning));
+        assert_eq!(results[0].message, "`sleep 5` timed out after 50ms");
+
+        // Background processes keep the pipes open, but must not keep envcheck waiting
+        let started = Instant::now();
+        let results = validate(CommandCheck {
+            run: "sleep 3 & echo hi".to_string(),
+            shell: true,
+            stdout_matches: Some("^hi$".to_string()),
+            ..Default::default()
+        });
+        assert!(matches!(results[0].status, ValidationStatus::Success));
+        let results = validate(CommandCheck {
+            run: "sleep 100 & wait".to_string(),
+            shell: true,
+            timeout: Some(Duration::from_millis(50)),
+            ..Default::default()
+        });
+        assert_eq!(results[0].message, "`sleep 100 & wait` timed out after 50ms");
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let results = validate(CommandCheck {
+            run: "envcheck-no-such-command".to_string(),
+            required: true,
+            ..Default::default()
+        });
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+        assert_eq!(results[0].message, "Failed to run `envcheck-no-such-command`");
+    }
+}
@@ -0,0 +1,282 @@
+use super::{message_buffer, read_cstr, Probe, ProbeError, Target};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+const CLIENT_LONG_PASSWORD: u32 = 0x0000_0001;
+const CLIENT_CONNECT_WITH_DB: u32 = 0x0000_0008;
+const CLIENT_PROTOCOL_41: u32 = 0x0000_0200;
+const CLIENT_SECURE_CONNECTION: u32 = 0x0000_8000;
+const CLIENT_PLUGIN_AUTH: u32 = 0x0008_0000;
+
+const NATIVE_PASSWORD: &str = "mysql_native_password";
+const CACHING_SHA2_PASSWORD: &str = "caching_sha2_password";
+
+/// Reads the server greeting and authenticates, selecting the database if one is set.
+pub(super) fn probe(target: &Target, timeout: Duration) -> Result<Probe, ProbeError> {
+    let mut connection = Connection {
+        stream: target.connect(timeout)?,
+        sequence: 0,
+    };
+
+    let greeting = connection.receive()?;
+    let handshake = Handshake::parse(&greeting)?;
+    let password = target.password.as_deref().unwrap_or_default();
+
+    let mut capabilities = CLIENT_LONG_PASSWORD | CLIENT_PROTOCOL_41 | CLIENT_SECURE_CONNECTION | CLIENT_PLUGIN_AUTH;
+    if target.database.is_some() {
+        capabilities |= CLIENT_CONNECT_WITH_DB;
+    }
+    let mut plugin = handshake.plugin.clone();
+    if plugin != CACHING_SHA2_PASSWORD {
+        plugin = NATIVE_PASSWORD.to_string();
+    }
+    let auth = scramble(&plugin, password, &handshake.nonce);
+
+    let mut response = capabilities.to_le_bytes().to_vec();
+    response.extend_from_slice(&(16u32 << 20).to_le_bytes());
+    response.push(45); // utf8mb4_general_ci
+    response.extend_from_slice(&[0; 23]);
+    response.extend_from_slice(target.user.as_bytes());
+    response.push(0);
+    response.push(auth.len() as u8);
+    response.extend_from_slice(&auth);
+    if let Some(database) = &target.database {
+        response.extend_from_slice(database.as_bytes());
+        response.push(0);
+    }
+    response.extend_from_slice(plugin.as_bytes());
+    response.push(0);
+    connection.send(&response)?;
+
+    loop {
+        let packet = connection.receive()?;
+        match packet.first() {
+            Some(0x00) => break,
+            Some(0xFF) => return Err(classify_error(&packet)),
+            Some(0xFE) => {
+                // Auth switch: the server wants another plugin with a fresh nonce
+                let mut data = &packet[1..];
+                let plugin = read_cstr(&mut data).unwrap_or_default();
+                if plugin != NATIVE_PASSWORD && plugin != CACHING_SHA2_PASSWORD {
+                    return Err(ProbeError::Protocol(format!(
+                        "unsupported authentication plugin '{}'",
+                        plugin
+                    )));
+                }
+                let nonce = data.strip_suffix(&[0]).unwrap_or(data);
+                connection.send(&scramble(&plugin, password, nonce))?;
+            }
+            Some(0x01) => match packet.get(1) {
+                // caching_sha2_password fast path; the OK packet follows
+                Some(0x03) => {}
+                Some(0x04) => {
+                    return Err(ProbeError::Protocol(
+                        "caching_sha2_password requires a full authentication over TLS; \
+                         log in once with the mysql client to populate the server's cache"
+                            .to_string(),
+                    ))
+                }
+                _ => return Err(ProbeError::Protocol("unexpected authentication data".to_string())),
+            },
+            _ => return Err(ProbeError::Protocol("unexpected authentication response".to_string())),
+        }
+    }
+
+    // COM_QUIT
+    connection.sequence = 0;
+    let _ = connection.send(&[0x01]);
+
+    let version = handshake.version;
+    let version = version.strip_prefix("5.5.5-").unwrap_or(&version).to_string();
+    Ok(Probe { version, schema_exists: None })
+}
+
+struct Connection {
+    stream: TcpStream,
+    sequence: u8,
+}
+
+impl Connection {
+    fn send(&mut self, payload: &[u8]) -> std::io::Result<()> {
+        let mut packet = (payload.len() as u32).to_le_bytes()[..3].to_vec();
+        packet.push(self.sequence);
+        packet.extend_from_slice(payload);
+        self.sequence = self.sequence.wrapping_add(1);
+        self.stream.write_all(&packet)
+    }
+
+    fn receive(&mut self) -> Result<Vec<u8>, ProbeError> {
+        let mut header = [0; 4];
+        self.stream.read_exact(&mut header)?;
+        let len = u32::from_le_bytes([header[0], header[1], header[2], 0]) as usize;
+        self.sequence = header[3].wrapping_add(1);
+        let mut payload = message_buffer(len)?;
+        self.stream.read_exact(&mut payload)?;
+        Ok(payload)
+    }
+}
+
+/// The fields of the initial handshake packet (protocol version 10) we need.
+#[derive(Debug)]
+struct Handshake {
+    version: String,
+    nonce: Vec<u8>,
+    plugin: String,
+}
+
+impl Handshake {
+    fn parse(packet: &[u8]) -> Result<Self, ProbeError> {
+        let invalid = || ProbeError::Protocol("invalid handshake packet".to_string());
+        match packet.first() {
+            Some(10) => {}
+            // Sent instead of a greeting, e.g. when the host is not allowed to connect
+            Some(0xFF) => return Err(classify_error(packet)),
+            _ => return Err(invalid()),
+        }
+
+        let mut data = &packet[1..];
+        let version = read_cstr(&mut data).ok_or_else(invalid)?;
+        // connection id (4), nonce part 1 (8), filler (1), capabilities (2),
+        // charset (1), status (2), upper capabilities (2), nonce length (1), reserved (10)
+        if data.len() < 31 {
+            return Err(invalid());
+        }
+        let mut nonce = data[4..12].to_vec();
+        let mut rest = &data[31..];
+        let part2 = rest.iter().position(|&b| b == 0).unwrap_or(rest.len()).min(12);
+        nonce.extend_from_slice(&rest[..part2]);
+        rest = rest.get(13..).unwrap_or_default();
+        let plugin = read_cstr(&mut rest).unwrap_or_default();
+
+        Ok(Self { version, nonce, plugin })
+    }
+}
+
+/// Computes the auth response for `plugin`; an empty password sends no data.
+fn scramble(plugin: &str, password: &str, nonce: &[u8]) -> Vec<u8> {
+    if password.is_empty() {
+        return Vec::new();
+    }
+    if plugin == CACHING_SHA2_PASSWORD {
+        // SHA256(password) XOR SHA256(SHA256(SHA256(password)) + nonce)
+        let hash = Sha256::digest(password);
+        let mut salted = Sha256::new();
+        salted.update(Sha256::digest(hash));
+        salted.update(nonce);
+        hash.iter().zip(salted.finalize()).map(|(a, b)| a ^ b).collect()
+    } else {
+        // SHA1(password) XOR SHA1(nonce + SHA1(SHA1(password)))
+        let hash = Sha1::digest(password);
+        let mut salted = Sha1::new();
+        salted.update(nonce);
+        salted.update(Sha1::digest(hash));
+        hash.iter().zip(salted.finalize()).map(|(a, b)| a ^ b).collect()
+    }
+}
+
+/// Maps an ERR packet to a failure kind using its error code.
+fn classify_error(packet: &[u8]) -> ProbeError {
+    let code = packet.get(1..3).map(|code| u16::from_le_bytes([code[0], code[1]]));
+    let message = packet.get(3..).unwrap_or_default();
+    // Skip the '#' marker and five character SQLSTATE sent with CLIENT_PROTOCOL_41
+    let message = match message.first() {
+        Some(b'#') => message.get(6..).unwrap_or_default(),
+        _ => message,
+    };
+    let message = String::from_utf8_lossy(message).into_owned();
+
+    match code {
+        // Access denied for user, access denied to database, host not allowed
+        Some(1044 | 1045 | 1130) => ProbeError::Auth(message),
+        // Unknown database
+        Some(1049) => ProbeError::MissingDatabase,
+        _ => ProbeError::Protocol(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Kind;
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    const NONCE: &[u8; 20] = b"abcdefghijklmnopqrst";
+
+    fn packet(sequence: u8, payload: &[u8]) -> Vec<u8> {
+        let mut packet = (payload.len() as u32).to_le_bytes()[..3].to_vec();
+        packet.push(sequence);
+        packet.extend_from_slice(payload);
+        packet
+    }
+
+    /// Serves one mysql_native_password session that accepts `password`.
+    fn stub_server(password: &'static str) -> (u16, thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let mut greeting = vec![10];
+            greeting.extend_from_slice(b"5.5.5-10.11.6-MariaDB\0");
+            greeting.extend_from_slice(&[1, 0, 0, 0]);
+            greeting.extend_from_slice(&NONCE[..8]);
+            greeting.extend_from_slice(&[0, 0xff, 0xf7, 45, 2, 0, 0xff, 0x81, 21]);
+            greeting.extend_from_slice(&[0; 10]);
+            greeting.extend_from_slice(&NONCE[8..]);
+            greeting.push(0);
+            greeting.extend_from_slice(b"mysql_native_password\0");
+            stream.write_all(&packet(0, &greeting)).unwrap();
+
+            let mut header = [0; 4];
+            stream.read_exact(&mut header).unwrap();
+            let mut response = vec![0; u32::from_le_bytes([header[0], header[1], header[2], 0]) as usize];
+            stream.read_exact(&mut response).unwrap();
+
+            let expected = scramble(NATIVE_PASSWORD, password, NONCE);
+            let accepted = response.windows(expected.len()).any(|window| window == expected.as_slice());
+            if accepted {
+                stream.write_all(&packet(2, &[0, 0, 0, 2, 0, 0, 0])).unwrap();
+            } else {
+                let mut error = vec![0xFF, 0x15, 0x04];
+                error.extend_from_slice(b"#28000Access denied for user 'app'@'localhost'");
+                stream.write_all(&packet(2, &error)).unwrap();
+            }
+        });
+        (port, handle)
+    }
+
+    fn target(port: u16, password: &str) -> Target {
+        Target {
+            kind: Kind::Mysql,
+            host: "127.0.0.1".to_string(),
+            port,
+            user: "app".to_string(),
+            password: Some(password.to_string()),
+            database: Some("app".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_native_password_session() {
+        let (port, server) = stub_server("secret");
+        let session = probe(&target(port, "secret"), Duration::from_secs(5)).unwrap();
+        server.join().unwrap();
+        assert_eq!(session.version, "10.11.6-MariaDB");
+
+        let (port, server) = stub_server("secret");
+        let error = probe(&target(port, "wrong"), Duration::from_secs(5)).unwrap_err();
+        server.join().unwrap();
+        assert_eq!(error, ProbeError::Auth("Access denied for user 'app'@'localhost'".to_string()));
+    }
+
+    #[test]
+    fn test_classify_error() {
+        let mut packet = vec![0xFF, 0x19, 0x04];
+        packet.extend_from_slice(b"#42000Unknown database 'app'");
+        assert_eq!(classify_error(&packet), ProbeError::MissingDatabase);
+    }
+}
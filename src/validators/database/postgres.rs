@@ -0,0 +1,425 @@
+use super::{message_buffer, read_cstr, Probe, ProbeError, Target};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+const PROTOCOL_VERSION: i32 = 196_608; // 3.0
+const SCRAM_SHA_256: &str = "SCRAM-SHA-256";
+
+/// Starts a session, authenticates and optionally looks up `schema`.
+pub(super) fn probe(target: &Target, schema: Option<&str>, timeout: Duration) -> Result<Probe, ProbeError> {
+    let mut stream = target.connect(timeout)?;
+    let version = startup(&mut stream, target)?;
+
+    let schema_exists = match schema {
+        Some(schema) => {
+            let query = format!(
+                "SELECT 1 FROM pg_namespace WHERE nspname = '{}'",
+                schema.replace('\'', "''")
+            );
+            Some(simple_query(&mut stream, &query)? > 0)
+        }
+        None => None,
+    };
+
+    let _ = send(&mut stream, b'X', &[]);
+    Ok(Probe { version, schema_exists })
+}
+
+/// Sends the startup message and answers authentication requests until the
+/// server is ready, returning its `server_version`.
+fn startup(stream: &mut TcpStream, target: &Target) -> Result<String, ProbeError> {
+    let database = target.database.as_deref().unwrap_or(&target.user);
+    let mut body = PROTOCOL_VERSION.to_be_bytes().to_vec();
+    for (key, value) in [("user", target.user.as_str()), ("database", database)] {
+        body.extend_from_slice(key.as_bytes());
+        body.push(0);
+        body.extend_from_slice(value.as_bytes());
+        body.push(0);
+    }
+    body.push(0);
+    let mut message = ((body.len() + 4) as i32).to_be_bytes().to_vec();
+    message.extend(body);
+    stream.write_all(&message)?;
+
+    let mut version = String::new();
+    let mut scram: Option<Scram> = None;
+    loop {
+        let (tag, payload) = receive(stream)?;
+        match tag {
+            b'R' => {
+                let code = payload
+                    .get(..4)
+                    .map(|code| i32::from_be_bytes(code.try_into().expect("4 bytes")))
+                    .ok_or_else(|| ProbeError::Protocol("truncated authentication request".to_string()))?;
+                let data = &payload[4..];
+                match code {
+                    0 => {}
+                    3 => send(stream, b'p', &cstr(password(target)?))?,
+                    5 => {
+                        let hash = md5_password(&target.user, password(target)?, data);
+                        send(stream, b'p', &cstr(&hash))?;
+                    }
+                    10 => {
+                        let mut mechanisms = data;
+                        let mut supported = false;
+                        while let Some(mechanism) = read_cstr(&mut mechanisms).filter(|m| !m.is_empty()) {
+                            supported |= mechanism == SCRAM_SHA_256;
+                        }
+                        if !supported {
+                            return Err(ProbeError::Protocol(
+                                "the server offers no supported SASL mechanism".to_string(),
+                            ));
+                        }
+                        let state = Scram::new(password(target)?)?;
+                        let first = state.client_first();
+                        let mut body = cstr(SCRAM_SHA_256);
+                        body.extend_from_slice(&(first.len() as i32).to_be_bytes());
+                        body.extend_from_slice(first.as_bytes());
+                        send(stream, b'p', &body)?;
+                        scram = Some(state);
+                    }
+                    11 => {
+                        let state = scram
+                            .as_mut()
+                            .ok_or_else(|| ProbeError::Protocol("unexpected SASL continue".to_string()))?;
+                        let server_first = String::from_utf8_lossy(data);
+                        let client_final = state.client_final(&server_first).map_err(ProbeError::Protocol)?;
+                        send(stream, b'p', client_final.as_bytes())?;
+                    }
+                    12 => {
+                        let verified = scram
+                            .as_ref()
+                            .is_some_and(|state| state.verify_server(&String::from_utf8_lossy(data)));
+                        if !verified {
+                            return Err(ProbeError::Protocol(
+                                "the server's SCRAM signature is invalid".to_string(),
+                            ));
+                        }
+                    }
+                    code => {
+                        return Err(ProbeError::Protocol(format!(
+                            "unsupported authentication method (code {})",
+                            code
+                        )))
+                    }
+                }
+            }
+            b'S' => {
+                let mut data = payload.as_slice();
+                if let (Some(name), Some(value)) = (read_cstr(&mut data), read_cstr(&mut data)) {
+                    if name == "server_version" {
+                        version = value;
+                    }
+                }
+            }
+            b'E' => return Err(classify_error(&payload)),
+            b'Z' => return Ok(version),
+            _ => {}
+        }
+    }
+}
+
+/// Runs a query with the simple query protocol and returns the number of rows.
+fn simple_query(stream: &mut TcpStream, query: &str) -> Result<usize, ProbeError> {
+    send(stream, b'Q', &cstr(query))?;
+    let mut rows = 0;
+    let mut error = None;
+    loop {
+        match receive(stream)? {
+            (b'D', _) => rows += 1,
+            (b'E', payload) => error = Some(classify_error(&payload)),
+            (b'Z', _) => return error.map_or(Ok(rows), Err),
+            _ => {}
+        }
+    }
+}
+
+fn password(target: &Target) -> Result<&str, ProbeError> {
+    target
+        .password
+        .as_deref()
+        .ok_or_else(|| ProbeError::Auth("a password is required but the URL has none".to_string()))
+}
+
+/// Maps an ErrorResponse to a failure kind using its SQLSTATE code.
+fn classify_error(payload: &[u8]) -> ProbeError {
+    let mut code = String::new();
+    let mut message = String::new();
+    let mut data = payload;
+    while let Some((&field, rest)) = data.split_first().filter(|(&field, _)| field != 0) {
+        data = rest;
+        let value = read_cstr(&mut data).unwrap_or_default();
+        match field {
+            b'C' => code = value,
+            b'M' => message = value,
+            _ => {}
+        }
+    }
+
+    match code.as_str() {
+        "3D000" => ProbeError::MissingDatabase,
+        // pg_hba.conf only has `hostssl` entries for this client
+        "28000" if message.contains("SSL off") || message.contains("no encryption") => {
+            ProbeError::TlsRequired(message)
+        }
+        code if code.starts_with("28") => ProbeError::Auth(message),
+        _ => ProbeError::Protocol(format!("{} ({})", message, code)),
+    }
+}
+
+fn send(stream: &mut TcpStream, tag: u8, body: &[u8]) -> std::io::Result<()> {
+    let mut message = vec![tag];
+    message.extend_from_slice(&((body.len() + 4) as i32).to_be_bytes());
+    message.extend_from_slice(body);
+    stream.write_all(&message)
+}
+
+fn receive(stream: &mut TcpStream) -> Result<(u8, Vec<u8>), ProbeError> {
+    let mut header = [0; 5];
+    stream.read_exact(&mut header)?;
+    let len = i32::from_be_bytes(header[1..].try_into().expect("4 bytes"));
+    let len = len
+        .checked_sub(4)
+        .and_then(|len| usize::try_from(len).ok())
+        .ok_or_else(|| ProbeError::Protocol("invalid message length".to_string()))?;
+    let mut payload = message_buffer(len)?;
+    stream.read_exact(&mut payload)?;
+    Ok((header[0], payload))
+}
+
+fn cstr(value: &str) -> Vec<u8> {
+    let mut bytes = value.as_bytes().to_vec();
+    bytes.push(0);
+    bytes
+}
+
+/// `md5` + md5(md5(password + user) + salt), as hex.
+fn md5_password(user: &str, password: &str, salt: &[u8]) -> String {
+    let inner = hex(&Md5::digest(format!("{}{}", password, user)));
+    let mut outer = Md5::new();
+    outer.update(inner.as_bytes());
+    outer.update(salt);
+    format!("md5{}", hex(&outer.finalize()))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Client side of SCRAM-SHA-256 (RFC 7677) without channel binding.
+struct Scram {
+    password: String,
+    client_first_bare: String,
+    nonce: String,
+    auth_message: String,
+    salted_password: Vec<u8>,
+}
+
+impl Scram {
+    fn new(password: &str) -> Result<Self, ProbeError> {
+        let mut random = [0u8; 18];
+        getrandom::getrandom(&mut random)
+            .map_err(|e| ProbeError::Protocol(format!("cannot generate a SCRAM nonce: {}", e)))?;
+        // PostgreSQL takes the user from the startup message, so it is left empty here
+        Ok(Self::with_nonce(password, "", &BASE64.encode(random)))
+    }
+
+    fn with_nonce(password: &str, user: &str, nonce: &str) -> Self {
+        Self {
+            password: password.to_string(),
+            client_first_bare: format!("n={},r={}", user, nonce),
+            nonce: nonce.to_string(),
+            auth_message: String::new(),
+            salted_password: Vec::new(),
+        }
+    }
+
+    fn client_first(&self) -> String {
+        format!("n,,{}", self.client_first_bare)
+    }
+
+    fn client_final(&mut self, server_first: &str) -> Result<String, String> {
+        let mut server_nonce = None;
+        let mut salt = None;
+        let mut iterations = None;
+        for attribute in server_first.split(',') {
+            match attribute.split_once('=') {
+                Some(("r", value)) => server_nonce = Some(value),
+                Some(("s", value)) => salt = BASE64.decode(value).ok(),
+                Some(("i", value)) => iterations = value.parse::<u32>().ok(),
+                _ => {}
+            }
+        }
+        let (server_nonce, salt, iterations) = match (server_nonce, salt, iterations) {
+            (Some(nonce), Some(salt), Some(iterations)) if nonce.starts_with(&self.nonce) => {
+                (nonce, salt, iterations)
+            }
+            _ => return Err("invalid SCRAM server-first message".to_string()),
+        };
+
+        let mut salted_password = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(self.password.as_bytes(), &salt, iterations, &mut salted_password);
+        let client_key = hmac(&salted_password, b"Client Key");
+        let stored_key = Sha256::digest(client_key);
+
+        let without_proof = format!("c=biws,r={}", server_nonce);
+        self.auth_message = format!("{},{},{}", self.client_first_bare, server_first, without_proof);
+        let signature = hmac(&stored_key, self.auth_message.as_bytes());
+        let proof: Vec<u8> = client_key.iter().zip(signature).map(|(k, s)| k ^ s).collect();
+        self.salted_password = salted_password.to_vec();
+
+        Ok(format!("{},p={}", without_proof, BASE64.encode(proof)))
+    }
+
+    fn verify_server(&self, server_final: &str) -> bool {
+        let server_key = hmac(&self.salted_password, b"Server Key");
+        let expected = BASE64.encode(hmac(&server_key, self.auth_message.as_bytes()));
+        server_final.strip_prefix("v=") == Some(expected.as_str())
+    }
+}
+
+fn hmac(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(message);
+    mac.finalize().into_bytes().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Kind;
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn test_scram_rfc7677_vector() {
+        let mut scram = Scram::with_nonce("pencil", "user", "rOprNGfwEbeRWgbNEkqO");
+        let server_first = "r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096";
+        assert_eq!(
+            scram.client_final(server_first).unwrap(),
+            "c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ="
+        );
+        assert!(scram.verify_server("v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4="));
+    }
+
+    fn message(tag: u8, body: &[u8]) -> Vec<u8> {
+        let mut message = vec![tag];
+        message.extend_from_slice(&((body.len() + 4) as i32).to_be_bytes());
+        message.extend_from_slice(body);
+        message
+    }
+
+    fn read_message(stream: &mut TcpStream) -> (u8, Vec<u8>) {
+        let mut header = [0; 5];
+        stream.read_exact(&mut header).unwrap();
+        let len = i32::from_be_bytes(header[1..].try_into().unwrap()) as usize - 4;
+        let mut body = vec![0; len];
+        stream.read_exact(&mut body).unwrap();
+        (header[0], body)
+    }
+
+    /// Serves one MD5-authenticated session that accepts `password` and
+    /// answers the schema lookup with a single row.
+    fn stub_server(password: &'static str) -> (u16, thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut len = [0; 4];
+            stream.read_exact(&mut len).unwrap();
+            let mut startup = vec![0; i32::from_be_bytes(len) as usize - 4];
+            stream.read_exact(&mut startup).unwrap();
+
+            let salt = [1, 2, 3, 4];
+            let mut auth = 5i32.to_be_bytes().to_vec();
+            auth.extend_from_slice(&salt);
+            stream.write_all(&message(b'R', &auth)).unwrap();
+
+            let (_, response) = read_message(&mut stream);
+            if response != cstr(&md5_password("app", password, &salt)) {
+                let error = b"SFATAL\0C28P01\0Mpassword authentication failed for user \"app\"\0\0";
+                stream.write_all(&message(b'E', error)).unwrap();
+                return;
+            }
+            stream.write_all(&message(b'R', &0i32.to_be_bytes())).unwrap();
+            stream.write_all(&message(b'S', b"server_version\x0016.1 (Debian 16.1-1)\0")).unwrap();
+            stream.write_all(&message(b'Z', b"I")).unwrap();
+
+            let (tag, _) = read_message(&mut stream);
+            assert_eq!(tag, b'Q');
+            stream.write_all(&message(b'D', &[0, 1, 0, 0, 0, 1, b'1'])).unwrap();
+            stream.write_all(&message(b'C', b"SELECT 1\0")).unwrap();
+            stream.write_all(&message(b'Z', b"I")).unwrap();
+        });
+        (port, handle)
+    }
+
+    fn target(port: u16, password: &str) -> Target {
+        Target {
+            kind: Kind::Postgres,
+            host: "127.0.0.1".to_string(),
+            port,
+            user: "app".to_string(),
+            password: Some(password.to_string()),
+            database: Some("app".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_md5_session() {
+        let (port, server) = stub_server("secret");
+        let session = probe(&target(port, "secret"), Some("public"), Duration::from_secs(5)).unwrap();
+        server.join().unwrap();
+        assert_eq!(session.version, "16.1 (Debian 16.1-1)");
+        assert_eq!(session.schema_exists, Some(true));
+
+        let (port, server) = stub_server("secret");
+        let error = probe(&target(port, "wrong"), None, Duration::from_secs(5)).unwrap_err();
+        server.join().unwrap();
+        assert_eq!(
+            error,
+            ProbeError::Auth("password authentication failed for user \"app\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_wrong_service_and_tls_only() {
+        // An HTTP server answers the startup message with "HTTP/1.1 400 ..."
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 8];
+            let _ = stream.read(&mut request);
+            stream.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n").unwrap();
+        });
+        let error = probe(&target(port, "secret"), None, Duration::from_secs(5)).unwrap_err();
+        server.join().unwrap();
+        assert!(matches!(error, ProbeError::Protocol(message) if message.contains("right port")));
+
+        // A length near i32::MIN must not overflow
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 8];
+            let _ = stream.read(&mut request);
+            stream.write_all(&[b'R', 0x80, 0, 0, 1]).unwrap();
+        });
+        let error = probe(&target(port, "secret"), None, Duration::from_secs(5)).unwrap_err();
+        server.join().unwrap();
+        assert_eq!(error, ProbeError::Protocol("invalid message length".to_string()));
+
+        let mut payload = b"SFATAL\0C28000\0".to_vec();
+        payload.extend_from_slice(
+            b"Mno pg_hba.conf entry for host \"10.0.0.5\", user \"app\", database \"app\", no encryption\0\0",
+        );
+        assert!(matches!(classify_error(&payload), ProbeError::TlsRequired(_)));
+    }
+}
@@ -0,0 +1,190 @@
+use super::{message_buffer, Probe, ProbeError, Target};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// Authenticates, selects the database index if one is set and reads `redis_version`.
+pub(super) fn probe(target: &Target, timeout: Duration) -> Result<Probe, ProbeError> {
+    let stream = target.connect(timeout)?;
+    let mut connection = Connection {
+        reader: BufReader::new(stream.try_clone()?),
+        stream,
+    };
+
+    if let Some(password) = &target.password {
+        let mut auth = vec!["AUTH"];
+        if !target.user.is_empty() {
+            auth.push(&target.user);
+        }
+        auth.push(password);
+        connection.command(&auth).map_err(|e| e.or_auth())?;
+    }
+
+    if let Some(database) = &target.database {
+        connection.command(&["SELECT", database]).map_err(|e| match e {
+            Reply::Error(message) if message.contains("DB index") => ProbeError::MissingDatabase,
+            other => other.or_auth(),
+        })?;
+    }
+
+    let info = connection.command(&["INFO", "server"]).map_err(|e| e.or_auth())?;
+    let version = info
+        .lines()
+        .find_map(|line| line.strip_prefix("redis_version:"))
+        .ok_or_else(|| ProbeError::Protocol("INFO did not report redis_version".to_string()))?
+        .trim()
+        .to_string();
+
+    let _ = connection.command(&["QUIT"]);
+    Ok(Probe { version, schema_exists: None })
+}
+
+struct Connection {
+    stream: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+impl Connection {
+    /// Sends a command as a RESP array and reads a simple, integer or bulk reply.
+    fn command(&mut self, args: &[&str]) -> Result<String, Reply> {
+        let mut request = format!("*{}\r\n", args.len());
+        for arg in args {
+            request.push_str(&format!("${}\r\n{}\r\n", arg.len(), arg));
+        }
+        self.stream.write_all(request.as_bytes())?;
+
+        let mut line = String::new();
+        self.reader.read_line(&mut line)?;
+        let line = line.trim_end_matches(['\r', '\n']);
+        match line.split_at(line.len().min(1)) {
+            ("+" | ":", value) => Ok(value.to_string()),
+            ("-", message) => Err(Reply::Error(message.to_string())),
+            ("$", len) => {
+                let len: usize = len
+                    .parse()
+                    .map_err(|_| Reply::Failed(ProbeError::Protocol("invalid bulk reply".to_string())))?;
+                let mut body = message_buffer(len.saturating_add(2)).map_err(Reply::Failed)?;
+                self.reader.read_exact(&mut body)?;
+                body.truncate(len);
+                Ok(String::from_utf8_lossy(&body).into_owned())
+            }
+            _ => Err(Reply::Failed(ProbeError::Protocol(format!(
+                "unexpected reply '{}'",
+                line
+            )))),
+        }
+    }
+}
+
+/// A failed command: either an error reply from the server or a broken connection.
+enum Reply {
+    Error(String),
+    Failed(ProbeError),
+}
+
+impl Reply {
+    /// Treats the authentication errors Redis sends as `Auth` failures.
+    fn or_auth(self) -> ProbeError {
+        match self {
+            Reply::Error(message)
+                if message.starts_with("NOAUTH")
+                    || message.starts_with("WRONGPASS")
+                    || message.contains("invalid password") =>
+            {
+                ProbeError::Auth(message)
+            }
+            Reply::Error(message) => ProbeError::Protocol(message),
+            Reply::Failed(e) => e,
+        }
+    }
+}
+
+impl From<std::io::Error> for Reply {
+    fn from(e: std::io::Error) -> Self {
+        Reply::Failed(e.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Kind;
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serves one session on a server with 16 databases that requires `password`.
+    fn stub_server(password: &'static str) -> (u16, thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+            let mut authenticated = false;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 {
+                    return;
+                }
+                let count: usize = line.trim()[1..].parse().unwrap();
+                let mut args = Vec::new();
+                for _ in 0..count * 2 {
+                    let mut part = String::new();
+                    reader.read_line(&mut part).unwrap();
+                    args.push(part.trim().to_string());
+                }
+                let args: Vec<&str> = args.iter().skip(1).step_by(2).map(String::as_str).collect();
+                let reply = match args.as_slice() {
+                    ["AUTH", given] if *given == password => {
+                        authenticated = true;
+                        "+OK\r\n".to_string()
+                    }
+                    ["AUTH", _] => "-WRONGPASS invalid username-password pair\r\n".to_string(),
+                    _ if !authenticated => "-NOAUTH Authentication required.\r\n".to_string(),
+                    ["SELECT", index] if index.parse::<u32>().is_ok_and(|i| i < 16) => "+OK\r\n".to_string(),
+                    ["SELECT", _] => "-ERR DB index is out of range\r\n".to_string(),
+                    ["INFO", "server"] => {
+                        let info = "# Server\r\nredis_version:7.2.4\r\nredis_mode:standalone\r\n";
+                        format!("${}\r\n{}\r\n", info.len(), info)
+                    }
+                    _ => {
+                        writer.write_all(b"+OK\r\n").unwrap();
+                        return;
+                    }
+                };
+                writer.write_all(reply.as_bytes()).unwrap();
+            }
+        });
+        (port, handle)
+    }
+
+    fn target(port: u16, password: Option<&str>, database: &str) -> Target {
+        Target {
+            kind: Kind::Redis,
+            host: "127.0.0.1".to_string(),
+            port,
+            user: String::new(),
+            password: password.map(str::to_string),
+            database: Some(database.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_session() {
+        let timeout = Duration::from_secs(5);
+        let (port, server) = stub_server("secret");
+        let session = probe(&target(port, Some("secret"), "2"), timeout).unwrap();
+        server.join().unwrap();
+        assert_eq!(session.version, "7.2.4");
+
+        let (port, server) = stub_server("secret");
+        let error = probe(&target(port, Some("secret"), "42"), timeout).unwrap_err();
+        drop(server);
+        assert_eq!(error, ProbeError::MissingDatabase);
+
+        let (port, server) = stub_server("secret");
+        let error = probe(&target(port, None, "0"), timeout).unwrap_err();
+        drop(server);
+        assert_eq!(error, ProbeError::Auth("NOAUTH Authentication required.".to_string()));
+    }
+}
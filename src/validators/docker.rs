@@ -0,0 +1,512 @@
+use crate::config::DockerCheck;
+use crate::expand::{expand_path, expand_vars};
+use crate::validators::port::{connect_any, is_local_host};
+use crate::validators::tool::check_version_requirement;
+use crate::validators::{ValidationResult, Validator};
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::ToSocketAddrs;
+use std::path::Path;
+use std::time::Duration;
+
+const DEFAULT_SOCKET: &str = "/var/run/docker.sock";
+const TIMEOUT: Duration = Duration::from_secs(5);
+const COMPOSE_SERVICE_LABEL: &str = "com.docker.compose.service";
+const COMPOSE_PROJECT_LABEL: &str = "com.docker.compose.project";
+
+pub struct DockerValidator {
+    check: DockerCheck,
+}
+
+/// The parts of a compose file the service check needs.
+struct ComposeFile {
+    path: String,
+    name: Option<String>,
+    services: Vec<String>,
+}
+
+/// Where the Docker Engine API is served.
+#[derive(Debug, Clone, PartialEq)]
+enum Endpoint {
+    Unix(String),
+    Tcp(String),
+}
+
+impl Endpoint {
+    fn parse(host: &str) -> std::result::Result<Self, String> {
+        if let Some(path) = host.strip_prefix("unix://") {
+            Ok(Endpoint::Unix(path.to_string()))
+        } else if let Some(addr) = host.strip_prefix("tcp://") {
+            Ok(Endpoint::Tcp(addr.trim_end_matches('/').to_string()))
+        } else if host.starts_with('/') {
+            Ok(Endpoint::Unix(host.to_string()))
+        } else {
+            Err(format!("Unsupported Docker host '{}'", host))
+        }
+    }
+
+    fn connect(&self) -> io::Result<Box<dyn Stream>> {
+        match self {
+            #[cfg(unix)]
+            Endpoint::Unix(path) => {
+                let stream = std::os::unix::net::UnixStream::connect(path)?;
+                stream.set_read_timeout(Some(TIMEOUT))?;
+                stream.set_write_timeout(Some(TIMEOUT))?;
+                Ok(Box::new(stream))
+            }
+            #[cfg(not(unix))]
+            Endpoint::Unix(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "unix sockets are not supported on this platform",
+            )),
+            Endpoint::Tcp(addr) => {
+                let stream = connect_any(addr.to_socket_addrs()?, TIMEOUT)?;
+                stream.set_read_timeout(Some(TIMEOUT))?;
+                stream.set_write_timeout(Some(TIMEOUT))?;
+                Ok(Box::new(stream))
+            }
+        }
+    }
+
+    /// Sends a GET request and returns the status code and body.
+    fn get(&self, path: &str) -> io::Result<(u16, String)> {
+        let mut stream = self.connect()?;
+        // HTTP/1.0 keeps the daemon from keeping the connection open
+        write!(stream, "GET {} HTTP/1.0\r\nHost: docker\r\n\r\n", path)?;
+        let mut raw = Vec::new();
+        stream.read_to_end(&mut raw)?;
+        parse_response(&raw).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid HTTP response"))
+    }
+
+    /// Fetches a JSON document, failing on non-200 responses.
+    fn get_json(&self, path: &str) -> std::result::Result<Value, String> {
+        let (status, body) = self.get(path).map_err(|e| e.to_string())?;
+        if status != 200 {
+            return Err(format!("{} returned status {}: {}", path, status, body.trim()));
+        }
+        serde_json::from_str(&body).map_err(|e| format!("invalid JSON from {}: {}", path, e))
+    }
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Endpoint::Unix(path) => write!(f, "{}", path),
+            Endpoint::Tcp(addr) => write!(f, "tcp://{}", addr),
+        }
+    }
+}
+
+trait Stream: Read + Write {}
+impl<T: Read + Write> Stream for T {}
+
+impl DockerValidator {
+    pub fn new(check: DockerCheck) -> Self {
+        Self { check }
+    }
+
+    fn endpoint(&self) -> std::result::Result<Endpoint, String> {
+        let host = match &self.check.host {
+            Some(host) => expand_vars(host).map_err(|e| e.to_string())?,
+            None => env::var("DOCKER_HOST")
+                .ok()
+                .filter(|host| !host.is_empty())
+                .unwrap_or_else(|| format!("unix://{}", DEFAULT_SOCKET)),
+        };
+        Endpoint::parse(&host)
+    }
+
+    /// The daemon's `tcp://` address, if it is on another machine.
+    pub fn remote_target(&self) -> Option<String> {
+        match self.endpoint() {
+            Ok(Endpoint::Tcp(addr)) => {
+                let host = addr.rsplit_once(':').map_or(addr.as_str(), |(host, _)| host);
+                (!is_local_host(host)).then(|| format!("tcp://{}", addr))
+            }
+            _ => None,
+        }
+    }
+
+    fn check_version(&self, endpoint: &Endpoint, results: &mut Vec<ValidationResult>) {
+        let version = endpoint
+            .get_json("/version")
+            .map(|info| info["Version"].as_str().unwrap_or("unknown").to_string());
+        let version = match version {
+            Ok(version) => version,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    "Failed to read the Docker server version".to_string(),
+                    Some(format!("Error: {}", e)),
+                ));
+                return;
+            }
+        };
+
+        match &self.check.version {
+            Some(requirement) if !check_version_requirement(&version, requirement) => {
+                results.push(ValidationResult::error(
+                    format!("Docker {} does not satisfy {}", version, requirement),
+                    Some(format!("Upgrade Docker to {}", requirement)),
+                ));
+            }
+            _ => results.push(ValidationResult::success(format!(
+                "Docker {} is running at {}",
+                version, endpoint
+            ))),
+        }
+    }
+
+    fn check_images(&self, endpoint: &Endpoint, results: &mut Vec<ValidationResult>) {
+        let local: BTreeSet<String> = match endpoint.get_json("/images/json") {
+            Ok(Value::Array(images)) => images
+                .iter()
+                .filter_map(|image| image["RepoTags"].as_array())
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(normalize_image)
+                .collect(),
+            Ok(_) => BTreeSet::new(),
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    "Failed to list Docker images".to_string(),
+                    Some(format!("Error: {}", e)),
+                ));
+                return;
+            }
+        };
+
+        for image in &self.check.images {
+            if local.contains(&normalize_image(image)) {
+                results.push(ValidationResult::success(format!("Image {} is present", image)));
+            } else {
+                results.push(ValidationResult::error(
+                    format!("Image {} is not present locally", image),
+                    Some(format!("Run `docker pull {}`", image)),
+                ));
+            }
+        }
+    }
+
+    fn check_services(&self, endpoint: &Endpoint, results: &mut Vec<ValidationResult>) {
+        let compose = match self.compose_file() {
+            Ok(compose) => compose,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Failed to read compose file {}", self.check.compose_file.as_deref().unwrap_or_default()),
+                    Some(e),
+                ));
+                return;
+            }
+        };
+
+        let compose_file = compose.as_ref().map(|compose| compose.path.clone());
+        let defined = compose.as_ref().map(|compose| compose.services.clone()).unwrap_or_default();
+        let project = self.project(compose.as_ref());
+
+        // Other compose projects on the machine may run services with the same names
+        let running: BTreeSet<String> = match endpoint.get_json("/containers/json") {
+            Ok(Value::Array(containers)) => containers
+                .iter()
+                .filter(|container| container["Labels"][COMPOSE_PROJECT_LABEL].as_str() == Some(project.as_str()))
+                .filter_map(|container| container["Labels"][COMPOSE_SERVICE_LABEL].as_str())
+                .map(|service| service.to_string())
+                .collect(),
+            Ok(_) => BTreeSet::new(),
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    "Failed to list running containers".to_string(),
+                    Some(format!("Error: {}", e)),
+                ));
+                return;
+            }
+        };
+
+        let up = |service: &str| match &compose_file {
+            Some(file) => format!("Run `docker compose -f {} up -d {}`", file, service),
+            None => format!("Run `docker compose up -d {}`", service),
+        };
+
+        for service in &self.check.services {
+            if compose_file.is_some() && !defined.contains(service) {
+                results.push(ValidationResult::error(
+                    format!("Compose service {} is not defined", service),
+                    Some(format!("Add {} to {}", service, compose_file.as_deref().unwrap_or_default())),
+                ));
+            } else if running.contains(service) {
+                results.push(ValidationResult::success(format!("Compose service {} is running", service)));
+            } else {
+                results.push(ValidationResult::error(
+                    format!("Compose service {} is not running", service),
+                    Some(up(service)),
+                ));
+            }
+        }
+
+        // Services that aren't required are only reported
+        for service in defined.iter().filter(|service| !self.check.services.contains(service)) {
+            if running.contains(service) {
+                results.push(ValidationResult::success(format!("Compose service {} is running", service)));
+            } else {
+                results.push(ValidationResult::warning(
+                    format!("Compose service {} is not running", service),
+                    Some(up(service)),
+                ));
+            }
+        }
+    }
+
+    /// Reads the project name and service names from the configured compose file.
+    fn compose_file(&self) -> std::result::Result<Option<ComposeFile>, String> {
+        let Some(compose_file) = &self.check.compose_file else {
+            return Ok(None);
+        };
+        let path = expand_path(compose_file).map_err(|e| e.to_string())?;
+        let contents = fs::read_to_string(&path).map_err(|e| format!("Error: {}", e))?;
+        let document: serde_yaml::Value =
+            serde_yaml::from_str(&contents).map_err(|e| format!("Invalid YAML: {}", e))?;
+        let services = document
+            .get("services")
+            .and_then(|services| services.as_mapping())
+            .map(|services| {
+                services
+                    .keys()
+                    .filter_map(|name| name.as_str().map(|name| name.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let name = document.get("name").and_then(|name| name.as_str()).map(str::to_string);
+        Ok(Some(ComposeFile { path, name, services }))
+    }
+
+    /// The compose project name, resolved the way `docker compose` does.
+    fn project(&self, compose: Option<&ComposeFile>) -> String {
+        let configured = self
+            .check
+            .project
+            .clone()
+            .or_else(|| env::var("COMPOSE_PROJECT_NAME").ok().filter(|name| !name.is_empty()))
+            .or_else(|| compose.and_then(|compose| compose.name.clone()));
+        let name = configured.unwrap_or_else(|| {
+            // A bare `docker-compose.yml` has an empty parent, which fails to canonicalize
+            let dir = compose
+                .and_then(|compose| Path::new(&compose.path).parent()?.canonicalize().ok())
+                .or_else(|| env::current_dir().ok())
+                .unwrap_or_default();
+            dir.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+        });
+        normalize_project(&name)
+    }
+}
+
+impl Validator for DockerValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        let mut results = Vec::new();
+
+        let endpoint = match self.endpoint() {
+            Ok(endpoint) => endpoint,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    "Invalid Docker host in config".to_string(),
+                    Some(format!("{}; use unix:///path/to/docker.sock or tcp://host:port", e)),
+                ));
+                return Ok(results);
+            }
+        };
+
+        match endpoint.get("/_ping") {
+            Ok((200, _)) => {}
+            Ok((status, body)) => {
+                results.push(ValidationResult::error(
+                    format!("Docker daemon at {} is unhealthy (Status: {})", endpoint, status),
+                    Some(body.trim().to_string()),
+                ));
+                return Ok(results);
+            }
+            Err(e) => {
+                let suggestion = match e.kind() {
+                    io::ErrorKind::PermissionDenied => {
+                        "Add your user to the docker group with `sudo usermod -aG docker $USER`, then log in again"
+                            .to_string()
+                    }
+                    io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused => {
+                        "Start Docker Desktop or run `sudo systemctl start docker`".to_string()
+                    }
+                    _ => format!("Error: {}", e),
+                };
+                let message = match e.kind() {
+                    io::ErrorKind::PermissionDenied => format!("Permission denied on Docker socket {}", endpoint),
+                    _ => format!("Docker daemon is not reachable at {}", endpoint),
+                };
+                results.push(ValidationResult::error(message, Some(suggestion)));
+                return Ok(results);
+            }
+        }
+
+        self.check_version(&endpoint, &mut results);
+        if !self.check.images.is_empty() {
+            self.check_images(&endpoint, &mut results);
+        }
+        if self.check.compose_file.is_some() || !self.check.services.is_empty() {
+            self.check_services(&endpoint, &mut results);
+        }
+
+        Ok(results)
+    }
+}
+
+/// Splits a raw HTTP response into status and body, decoding chunked bodies.
+fn parse_response(raw: &[u8]) -> Option<(u16, String)> {
+    let text = String::from_utf8_lossy(raw);
+    let (head, body) = text.split_once("\r\n\r\n")?;
+    let status = head.split_whitespace().nth(1)?.parse().ok()?;
+    let chunked = head.lines().any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("transfer-encoding") && value.trim().eq_ignore_ascii_case("chunked")
+        })
+    });
+    let body = if chunked { decode_chunked(body)? } else { body.to_string() };
+    Some((status, body))
+}
+
+fn decode_chunked(mut body: &str) -> Option<String> {
+    let mut decoded = String::new();
+    loop {
+        let (size, rest) = body.split_once("\r\n")?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+        if size == 0 {
+            return Some(decoded);
+        }
+        decoded.push_str(rest.get(..size)?);
+        body = rest.get(size..)?.strip_prefix("\r\n")?;
+    }
+}
+
+/// Normalizes an image reference so `postgres`, `library/postgres:latest`
+/// and `docker.io/library/postgres` compare equal.
+fn normalize_image(image: &str) -> String {
+    let image = image.strip_prefix("docker.io/").unwrap_or(image);
+    let image = image.strip_prefix("library/").unwrap_or(image);
+    let name = image.rsplit('/').next().unwrap_or(image);
+    if name.contains(':') || name.contains('@') {
+        image.to_string()
+    } else {
+        format!("{}:latest", image)
+    }
+}
+
+/// Lowercases a project name and drops characters compose doesn't allow.
+fn normalize_project(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == '_' || *c == '-')
+        .collect::<String>()
+        .trim_start_matches(['_', '-'])
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validators::ValidationStatus;
+
+    #[test]
+    fn test_parse_response() {
+        let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n3\r\n[1,\r\n2\r\n2]\r\n0\r\n\r\n";
+        assert_eq!(parse_response(raw), Some((200, "[1,2]".to_string())));
+        assert_eq!(parse_response(b"HTTP/1.0 500 Oops\r\n\r\nbad"), Some((500, "bad".to_string())));
+        assert_eq!(
+            Endpoint::parse("tcp://127.0.0.1:2375"),
+            Ok(Endpoint::Tcp("127.0.0.1:2375".to_string()))
+        );
+        assert!(Endpoint::parse("npipe:////./pipe/docker_engine").is_err());
+    }
+
+    #[test]
+    fn test_normalize_project() {
+        assert_eq!(normalize_project("My.Project_1"), "myproject_1");
+        assert_eq!(normalize_project("-api"), "api");
+    }
+
+    #[test]
+    fn test_normalize_image() {
+        assert_eq!(normalize_image("postgres"), "postgres:latest");
+        assert_eq!(normalize_image("docker.io/library/postgres:16"), "postgres:16");
+        assert_eq!(normalize_image("ghcr.io/org/app"), "ghcr.io/org/app:latest");
+        assert_eq!(normalize_image("localhost:5000/app"), "localhost:5000/app:latest");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_daemon_over_unix_socket() {
+        use std::io::{BufRead, BufReader};
+        use std::os::unix::net::UnixListener;
+        use std::thread;
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("docker.sock");
+        let listener = UnixListener::bind(&socket).unwrap();
+        let server = thread::spawn(move || {
+            for _ in 0..4 {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request_line = String::new();
+                BufReader::new(&stream).read_line(&mut request_line).unwrap();
+                let body = match request_line.split_whitespace().nth(1).unwrap() {
+                    "/_ping" => "OK",
+                    "/version" => r#"{"Version":"24.0.7","ApiVersion":"1.43"}"#,
+                    "/images/json" => r#"[{"RepoTags":["postgres:16"]},{"RepoTags":null}]"#,
+                    "/containers/json" => {
+                        r#"[{"Labels":{"com.docker.compose.service":"db","com.docker.compose.project":"other"}},
+                            {"Labels":{"com.docker.compose.service":"cache","com.docker.compose.project":"other"}},
+                            {"Labels":{"com.docker.compose.service":"db","com.docker.compose.project":"shop"}}]"#
+                    }
+                    _ => "{}",
+                };
+                write!(stream, "HTTP/1.0 200 OK\r\nContent-Type: application/json\r\n\r\n{}", body).unwrap();
+            }
+        });
+
+        let compose_file = dir.path().join("docker-compose.yml");
+        fs::write(
+            &compose_file,
+            "name: Shop\nservices:\n  db:\n    image: postgres:16\n  cache:\n    image: redis\n",
+        )
+        .unwrap();
+
+        let check = DockerCheck {
+            host: Some(format!("unix://{}", socket.display())),
+            version: Some(">=20.10".to_string()),
+            images: vec!["postgres:16".to_string(), "redis".to_string()],
+            compose_file: Some(compose_file.to_str().unwrap().to_string()),
+            services: vec!["db".to_string()],
+            ..Default::default()
+        };
+        let results = DockerValidator::new(check).validate().unwrap();
+        server.join().unwrap();
+
+        let messages: Vec<&str> = results.iter().map(|r| r.message.as_str()).collect();
+        assert_eq!(messages[0], format!("Docker 24.0.7 is running at {}", socket.display()));
+        assert_eq!(
+            messages[1..],
+            [
+                "Image postgres:16 is present",
+                "Image redis is not present locally",
+                "Compose service db is running",
+                "Compose service cache is not running",
+            ]
+        );
+        assert!(matches!(results[2].status, ValidationStatus::Error));
+        assert_eq!(results[2].suggestion.as_deref(), Some("Run `docker pull redis`"));
+        assert!(matches!(results[4].status, ValidationStatus::Warning));
+
+        let check = DockerCheck {
+            host: Some(format!("unix://{}", dir.path().join("missing.sock").display())),
+            ..Default::default()
+        };
+        let results = DockerValidator::new(check).validate().unwrap();
+        assert!(results[0].message.starts_with("Docker daemon is not reachable at "));
+    }
+}
@@ -1,7 +1,19 @@
-use crate::config::FileCheck;
-use crate::validators::{ValidationResult, Validator};
+use crate::config::{FileCheck, FileFormat, KeyCheck};
+use crate::expand::{expand_path, ExpandError};
+use crate::validators::tool::check_version_requirement;
+use crate::validators::{ValidationResult, ValidationStatus, Validator};
+use crate::units::{format_duration, format_size};
 use anyhow::Result;
-use std::path::Path;
+use regex::{Regex, RegexBuilder};
+use serde_json::Value;
+use sha2::{Digest, Sha256, Sha512};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Maximum number of matched paths listed in a `max_count` failure.
+const MAX_LISTED_MATCHES: usize = 10;
 
 pub struct FileValidator {
     check: FileCheck,
@@ -11,63 +23,936 @@ impl FileValidator {
     pub fn new(check: FileCheck) -> Self {
         Self { check }
     }
-}
 
-impl Validator for FileValidator {
-    fn validate(&self) -> Result<Vec<ValidationResult>> {
-        let mut results = Vec::new();
-        let path = Path::new(&self.check.path);
+    /// Runs the type, permission, content and key checks against a path that exists.
+    fn check_existing(&self, path: &Path, results: &mut Vec<ValidationResult>) {
+        let file = &path.display().to_string();
 
-        if path.exists() {
-            let mut item_passed = true;
-            
-            if self.check.is_directory {
-                if path.is_dir() {
-                    results.push(ValidationResult::success(
-                        format!("Directory {} exists", self.check.path),
-                    ));
-                } else {
-                    item_passed = false;
-                    results.push(ValidationResult::error(
-                        format!("{} exists but is not a directory", self.check.path),
-                        Some(format!("Ensure {} is a directory", self.check.path)),
-                    ));
-                }
+        if !path.exists() {
+            let target = fs::read_link(path)
+                .map(|target| target.display().to_string())
+                .unwrap_or_default();
+            let message = format!("{} is a broken symlink to {}", file, target);
+            if self.check.required {
+                results.push(ValidationResult::error(
+                    message,
+                    Some(format!("Create {} or re-point the link with 'ln -sfn <target> {}'", target, file)),
+                ));
             } else {
+                results.push(ValidationResult::warning(format!("{} (optional)", message), None));
+            }
+            return;
+        }
+
+        let checks_symlink = self.check.symlink.is_some() || self.check.target.is_some();
+        if checks_symlink && !self.check_symlink(path, results) {
+            return;
+        }
+
+        if self.check.is_directory {
+            if path.is_dir() {
                 results.push(ValidationResult::success(
-                    format!("{} exists", self.check.path),
-                ));
-            }
-
-            if item_passed {
-                if let Some(required_perms) = self.check.permissions {
-                    #[cfg(unix)]
-                    {
-                        use std::os::unix::fs::PermissionsExt;
-                        if let Ok(metadata) = path.metadata() {
-                            let actual_perms = metadata.permissions().mode() & 0o777;
-                            if actual_perms == required_perms {
-                                results.push(ValidationResult::success(
-                                    format!("{} has correct permissions ({:o})", self.check.path, actual_perms),
-                                ));
-                            } else {
-                                results.push(ValidationResult::error(
-                                    format!("{} has permissions {:o}, but {:o} is required", 
-                                        self.check.path, actual_perms, required_perms),
-                                    Some(format!("Run 'chmod {:o} {}' to fix", required_perms, self.check.path)),
-                                ));
-                            }
-                        }
+                    format!("Directory {} exists", file),
+                ));
+            } else {
+                results.push(ValidationResult::error(
+                    format!("{} exists but is not a directory", file),
+                    Some(format!("Ensure {} is a directory", file)),
+                ));
+                return;
+            }
+        } else {
+            results.push(ValidationResult::success(
+                format!("{} exists", file),
+            ));
+        }
+
+        if self.has_permission_checks() {
+            #[cfg(unix)]
+            self.check_permissions(path, results);
+            #[cfg(not(unix))]
+            results.push(ValidationResult::warning(
+                format!("Permission check skipped for {} (not supported on this platform)", file),
+                None,
+            ));
+        }
+
+        if !self.check.is_directory && self.has_integrity_checks() {
+            self.check_integrity(path, results);
+        }
+
+        if self.check.max_age.is_some() || self.check.newer_than.is_some() {
+            self.check_freshness(path, results);
+        }
+
+        if !self.check.is_directory && self.has_content_checks() {
+            self.check_contents(path, results);
+        }
+
+        if !self.check.is_directory && !self.check.keys.is_empty() {
+            self.check_keys(path, results);
+        }
+    }
+
+    /// Expands a glob path, enforces `min_count`/`max_count` and runs the
+    /// per-file checks on every match, reporting only the files that fail.
+    fn validate_glob(&self, results: &mut Vec<ValidationResult>) {
+        let pattern = &self.check.path;
+        let mut unreadable = Vec::new();
+        let matches: Vec<PathBuf> = match glob::glob(pattern) {
+            Ok(paths) => paths
+                .filter_map(|entry| entry.map_err(|e| unreadable.push(e)).ok())
+                .filter(|path| path.is_dir() == self.check.is_directory)
+                .collect(),
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Invalid glob pattern {}", pattern),
+                    Some(format!("Error: {}", e)),
+                ));
+                return;
+            }
+        };
+
+        if let Some(first) = unreadable.first() {
+            let more = match unreadable.len() {
+                1 => String::new(),
+                n => format!(" and {} other path(s)", n - 1),
+            };
+            results.push(ValidationResult::warning(
+                format!("Could not read {}{} while matching {}", first.path().display(), more, pattern),
+                Some(format!("Error: {}", first.error())),
+            ));
+        }
+
+        let max_count = self.check.max_count.unwrap_or(usize::MAX);
+        let default_min = if self.check.required { 1 } else { 0 };
+        let min_count = self.check.min_count.unwrap_or(default_min).min(max_count);
+        let count = matches.len();
+
+        if count < min_count {
+            let message = format!("{} matched {} file(s), expected at least {}", pattern, count, min_count);
+            if self.check.required {
+                results.push(ValidationResult::error(
+                    message,
+                    Some(format!("Create files matching {}", pattern)),
+                ));
+            } else {
+                results.push(ValidationResult::warning(format!("{} (optional)", message), None));
+            }
+            return;
+        }
+
+        if count > max_count {
+            let listed = matches
+                .iter()
+                .take(MAX_LISTED_MATCHES)
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let more = if count > MAX_LISTED_MATCHES {
+                format!(" and {} more", count - MAX_LISTED_MATCHES)
+            } else {
+                String::new()
+            };
+            results.push(ValidationResult::error(
+                format!("{} matched {} file(s), expected at most {}", pattern, count, max_count),
+                Some(format!("Remove or untrack: {}{}", listed, more)),
+            ));
+        } else {
+            results.push(ValidationResult::success(format!(
+                "{} matched {} file(s)",
+                pattern, count
+            )));
+        }
+
+        if count == 0 || !self.has_per_file_checks() {
+            return;
+        }
+
+        let mut failures = Vec::new();
+        for path in &matches {
+            let mut file_results = Vec::new();
+            self.check_existing(path, &mut file_results);
+            failures.extend(
+                file_results
+                    .into_iter()
+                    .filter(|r| !matches!(r.status, ValidationStatus::Success)),
+            );
+        }
+
+        if failures.is_empty() {
+            results.push(ValidationResult::success(format!(
+                "All {} match(es) of {} passed checks",
+                count, pattern
+            )));
+        } else {
+            results.extend(failures);
+        }
+    }
+
+    /// Checks the `symlink` and `target` expectations, returning false if the
+    /// path is not the kind of entry the check expects.
+    fn check_symlink(&self, path: &Path, results: &mut Vec<ValidationResult>) -> bool {
+        let file = &path.display().to_string();
+        let link_target = fs::read_link(path).ok();
+
+        if self.check.symlink == Some(false) {
+            if let Some(actual) = link_target {
+                results.push(ValidationResult::error(
+                    format!("{} is a symlink to {}, but should not be a symlink", file, actual.display()),
+                    Some(format!("Replace {} with a regular file or directory", file)),
+                ));
+                return false;
+            }
+            results.push(ValidationResult::success(format!("{} is not a symlink", file)));
+            return true;
+        }
+
+        let actual = match link_target {
+            Some(actual) => actual,
+            None => {
+                let suggestion = match &self.check.target {
+                    Some(target) => format!("Run 'ln -sfn {} {}' to fix", target, file),
+                    None => format!("Replace {} with a symlink", file),
+                };
+                results.push(ValidationResult::error(
+                    format!("{} is not a symlink", file),
+                    Some(suggestion),
+                ));
+                return false;
+            }
+        };
+
+        match &self.check.target {
+            Some(expected) if !link_points_to(path, &actual, Path::new(expected)) => {
+                results.push(ValidationResult::error(
+                    format!("{} links to {}, but {} is expected", file, actual.display(), expected),
+                    Some(format!("Run 'ln -sfn {} {}' to fix", expected, file)),
+                ));
+            }
+            _ => results.push(ValidationResult::success(format!(
+                "{} is a symlink to {}",
+                file,
+                actual.display()
+            ))),
+        }
+        true
+    }
+
+    fn has_per_file_checks(&self) -> bool {
+        self.check.symlink.is_some()
+            || self.check.target.is_some()
+            || self.has_permission_checks()
+            || self.has_integrity_checks()
+            || self.check.max_age.is_some()
+            || self.check.newer_than.is_some()
+            || self.has_content_checks()
+            || !self.check.keys.is_empty()
+    }
+
+    fn has_integrity_checks(&self) -> bool {
+        self.check.sha256.is_some()
+            || self.check.sha512.is_some()
+            || self.check.min_size.is_some()
+            || self.check.max_size.is_some()
+    }
+
+    fn check_integrity(&self, path: &Path, results: &mut Vec<ValidationResult>) {
+        let file = &path.display().to_string();
+
+        if self.check.min_size.is_some() || self.check.max_size.is_some() {
+            match path.metadata() {
+                Ok(metadata) => {
+                    let size = metadata.len();
+                    let mut passed = true;
+                    if let Some(min_size) = self.check.min_size.filter(|min| size < *min) {
+                        passed = false;
+                        results.push(ValidationResult::error(
+                            format!("{} is {}, smaller than the minimum {}", file, format_size(size), format_size(min_size)),
+                            Some(format!("Regenerate or restore {}", file)),
+                        ));
                     }
-                    #[cfg(not(unix))]
-                    {
-                        results.push(ValidationResult::warning(
-                            format!("Permission check skipped for {} (not supported on this platform)", self.check.path),
-                            None,
+                    if let Some(max_size) = self.check.max_size.filter(|max| size > *max) {
+                        passed = false;
+                        results.push(ValidationResult::error(
+                            format!("{} is {}, larger than the maximum {}", file, format_size(size), format_size(max_size)),
+                            Some(format!("Regenerate or restore {}", file)),
                         ));
                     }
+                    if passed {
+                        results.push(ValidationResult::success(format!(
+                            "{} size ({}) is within limits",
+                            file,
+                            format_size(size)
+                        )));
+                    }
+                }
+                Err(e) => results.push(ValidationResult::error(
+                    format!("Failed to read size of {}", file),
+                    Some(format!("Error: {}", e)),
+                )),
+            }
+        }
+
+        if let Some(expected) = &self.check.sha256 {
+            check_digest(path, "SHA-256", expected, hash_file::<Sha256>(path), results);
+        }
+
+        if let Some(expected) = &self.check.sha512 {
+            check_digest(path, "SHA-512", expected, hash_file::<Sha512>(path), results);
+        }
+    }
+
+    fn check_freshness(&self, path: &Path, results: &mut Vec<ValidationResult>) {
+        let file = &path.display().to_string();
+        let modified = match path.metadata().and_then(|m| m.modified()) {
+            Ok(modified) => modified,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Failed to read modification time of {}", file),
+                    Some(format!("Error: {}", e)),
+                ));
+                return;
+            }
+        };
+
+        if let Some(max_age) = self.check.max_age {
+            // Files with a modification time in the future count as brand new
+            let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+            if age <= max_age {
+                results.push(ValidationResult::success(format!(
+                    "{} was modified {} ago",
+                    file,
+                    format_duration(age)
+                )));
+            } else {
+                results.push(ValidationResult::error(
+                    format!("{} was modified {} ago, older than {}", file, format_duration(age), format_duration(max_age)),
+                    Some(format!("Regenerate {}", file)),
+                ));
+            }
+        }
+
+        if let Some(other) = &self.check.newer_than {
+            match Path::new(other).metadata().and_then(|m| m.modified()) {
+                Ok(other_modified) if modified >= other_modified => {
+                    results.push(ValidationResult::success(format!(
+                        "{} is newer than {}",
+                        file, other
+                    )));
+                }
+                Ok(_) => results.push(ValidationResult::error(
+                    format!("{} is older than {}", file, other),
+                    Some(format!("Regenerate {} after changes to {}", file, other)),
+                )),
+                Err(e) => results.push(ValidationResult::error(
+                    format!("Cannot compare {} with {}", file, other),
+                    Some(format!("Error reading {}: {}", other, e)),
+                )),
+            }
+        }
+    }
+
+    fn has_permission_checks(&self) -> bool {
+        self.check.permissions.is_some()
+            || self.check.max_permissions.is_some()
+            || self.check.min_permissions.is_some()
+            || self.check.executable.is_some()
+            || self.check.owner.is_some()
+            || self.check.group.is_some()
+    }
+
+    #[cfg(unix)]
+    fn check_permissions(&self, path: &Path, results: &mut Vec<ValidationResult>) {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        let file = &path.display().to_string();
+        let metadata = match path.metadata() {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Failed to read permissions of {}", file),
+                    Some(format!("Error: {}", e)),
+                ));
+                return;
+            }
+        };
+        let actual_perms = metadata.permissions().mode() & 0o777;
+
+        if let Some(required_perms) = self.check.permissions {
+            if actual_perms == required_perms {
+                results.push(ValidationResult::success(
+                    format!("{} has correct permissions ({:o})", file, actual_perms),
+                ));
+            } else {
+                results.push(ValidationResult::error(
+                    format!("{} has permissions {:o}, but {:o} is required", 
+                        file, actual_perms, required_perms),
+                    Some(format!("Run 'chmod {:o} {}' to fix", required_perms, file)),
+                ));
+            }
+        }
+
+        if let Some(max_perms) = self.check.max_permissions {
+            if actual_perms & !max_perms == 0 {
+                results.push(ValidationResult::success(
+                    format!("{} permissions ({:o}) are within {:o}", file, actual_perms, max_perms),
+                ));
+            } else {
+                results.push(ValidationResult::error(
+                    format!("{} has permissions {:o}, which exceed {:o}", file, actual_perms, max_perms),
+                    Some(format!("Run 'chmod {:o} {}' to fix", actual_perms & max_perms, file)),
+                ));
+            }
+        }
+
+        if let Some(min_perms) = self.check.min_permissions {
+            if actual_perms & min_perms == min_perms {
+                results.push(ValidationResult::success(
+                    format!("{} permissions ({:o}) include {:o}", file, actual_perms, min_perms),
+                ));
+            } else {
+                results.push(ValidationResult::error(
+                    format!("{} has permissions {:o}, which are missing {:o}", 
+                        file, actual_perms, min_perms & !actual_perms),
+                    Some(format!("Run 'chmod {:o} {}' to fix", actual_perms | min_perms, file)),
+                ));
+            }
+        }
+
+        if let Some(executable) = self.check.executable {
+            let is_executable = actual_perms & 0o111 != 0;
+            match (executable, is_executable) {
+                (true, true) => results.push(ValidationResult::success(format!("{} is executable", file))),
+                (false, false) => results.push(ValidationResult::success(format!("{} is not executable", file))),
+                (true, false) => results.push(ValidationResult::error(
+                    format!("{} is not executable", file),
+                    Some(format!("Run 'chmod +x {}' to fix", file)),
+                )),
+                (false, true) => results.push(ValidationResult::error(
+                    format!("{} is executable but should not be", file),
+                    Some(format!("Run 'chmod -x {}' to fix", file)),
+                )),
+            }
+        }
+
+        if let Some(owner) = &self.check.owner {
+            check_id(file, "user", owner, metadata.uid(), "/etc/passwd", "chown", results);
+        }
+
+        if let Some(group) = &self.check.group {
+            check_id(file, "group", group, metadata.gid(), "/etc/group", "chgrp", results);
+        }
+    }
+
+    fn has_content_checks(&self) -> bool {
+        !self.check.contains.is_empty()
+            || !self.check.not_contains.is_empty()
+            || !self.check.matches.is_empty()
+            || !self.check.not_matches.is_empty()
+    }
+
+    fn build_pattern(pattern: &str) -> Regex {
+        // Patterns are matched line by line, so `^` and `$` anchor to line boundaries
+        RegexBuilder::new(pattern)
+            .multi_line(true)
+            .build()
+            .unwrap_or_else(|_| {
+                // Fallback to a literal match if the regex is invalid
+                Regex::new(&regex::escape(pattern)).expect("escaped pattern is a valid regex")
+            })
+    }
+
+    fn check_contents(&self, path: &Path, results: &mut Vec<ValidationResult>) {
+        let file = &path.display().to_string();
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Failed to read {}", file),
+                    Some(format!("Error: {}", e)),
+                ));
+                return;
+            }
+        };
+        let line_starts = line_starts(&content);
+
+        for needle in &self.check.contains {
+            match content.find(needle.as_str()) {
+                Some(offset) => results.push(ValidationResult::success(format!(
+                    "{} contains '{}' (line {})",
+                    file,
+                    needle,
+                    line_number(&line_starts, offset)
+                ))),
+                None => results.push(ValidationResult::error(
+                    format!("{} does not contain '{}'", file, needle),
+                    Some(format!("Add '{}' to {}", needle, file)),
+                )),
+            }
+        }
+
+        for needle in &self.check.not_contains {
+            let lines: Vec<usize> = content
+                .match_indices(needle.as_str())
+                .map(|(offset, _)| line_number(&line_starts, offset))
+                .collect();
+            self.push_forbidden_result(file, &format!("'{}'", needle), &lines, results);
+        }
+
+        for pattern in &self.check.matches {
+            match Self::build_pattern(pattern).find(&content) {
+                Some(m) => results.push(ValidationResult::success(format!(
+                    "{} matches pattern {} (line {})",
+                    file,
+                    pattern,
+                    line_number(&line_starts, m.start())
+                ))),
+                None => results.push(ValidationResult::error(
+                    format!("{} does not match pattern {}", file, pattern),
+                    Some(format!("Ensure {} contains a line matching: {}", file, pattern)),
+                )),
+            }
+        }
+
+        for pattern in &self.check.not_matches {
+            let lines: Vec<usize> = Self::build_pattern(pattern)
+                .find_iter(&content)
+                .map(|m| line_number(&line_starts, m.start()))
+                .collect();
+            self.push_forbidden_result(file, &format!("pattern {}", pattern), &lines, results);
+        }
+    }
+
+    fn detect_format(&self, path: &Path) -> Option<FileFormat> {
+        if self.check.format.is_some() {
+            return self.check.format;
+        }
+        match path.extension()?.to_str()? {
+            "json" => Some(FileFormat::Json),
+            "yaml" | "yml" => Some(FileFormat::Yaml),
+            "toml" => Some(FileFormat::Toml),
+            _ => None,
+        }
+    }
+
+    fn check_keys(&self, path: &Path, results: &mut Vec<ValidationResult>) {
+        let file = &path.display().to_string();
+        let format = match self.detect_format(path) {
+            Some(format) => format,
+            None => {
+                results.push(ValidationResult::error(
+                    format!("Cannot determine the format of {}", file),
+                    Some("Set 'format' to json, yaml or toml".to_string()),
+                ));
+                return;
+            }
+        };
+
+        let document = match parse_document(path, format) {
+            Ok(document) => document,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Failed to parse {} as {}", file, format_name(format)),
+                    Some(format!("Error: {}", e)),
+                ));
+                return;
+            }
+        };
+
+        for key_check in &self.check.keys {
+            self.check_key(file, &document, key_check, results);
+        }
+    }
+
+    fn check_key(&self, file: &str, document: &Value, key_check: &KeyCheck, results: &mut Vec<ValidationResult>) {
+        let key = &key_check.key;
+        let segments = match parse_selector(key) {
+            Ok(segments) => segments,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Invalid key selector '{}' for {}", key, file),
+                    Some(e),
+                ));
+                return;
+            }
+        };
+
+        let value = match select(document, &segments) {
+            Some(value) => value,
+            None => {
+                if key_check.exists == Some(false) {
+                    results.push(ValidationResult::success(format!(
+                        "{}: {} is not set",
+                        file, key
+                    )));
+                } else {
+                    results.push(ValidationResult::error(
+                        format!("{}: {} not found", file, key),
+                        Some(format!("Add {} to {}", key, file)),
+                    ));
+                }
+                return;
+            }
+        };
+
+        if key_check.exists == Some(false) {
+            results.push(ValidationResult::error(
+                format!("{}: {} is set but should not be", file, key),
+                Some(format!("Remove {} from {}", key, file)),
+            ));
+            return;
+        }
+
+        let actual = display_value(value);
+        let mut passed = true;
+
+        if let Some(expected) = &key_check.equals {
+            if !values_equal(value, expected) {
+                passed = false;
+                results.push(ValidationResult::error(
+                    format!("{}: {} is {}, expected {}", file, key, actual, display_value(expected)),
+                    Some(format!("Set {} to {} in {}", key, display_value(expected), file)),
+                ));
+            }
+        }
+
+        if let Some(pattern) = &key_check.matches {
+            if !Self::build_pattern(pattern).is_match(&actual) {
+                passed = false;
+                results.push(ValidationResult::error(
+                    format!("{}: {} is {}, which does not match pattern {}", file, key, actual, pattern),
+                    Some(format!("Ensure {} in {} matches pattern: {}", key, file, pattern)),
+                ));
+            }
+        }
+
+        if let Some(requirement) = &key_check.semver {
+            let version = actual.trim_start_matches('v');
+            if !check_version_requirement(version, requirement) {
+                passed = false;
+                results.push(ValidationResult::error(
+                    format!("{}: {} is {}, which does not meet requirement {}", file, key, actual, requirement),
+                    Some(format!("Update {} in {} to satisfy {}", key, file, requirement)),
+                ));
+            }
+        }
+
+        if passed {
+            results.push(ValidationResult::success(format!(
+                "{}: {} = {}",
+                file, key, actual
+            )));
+        }
+    }
+
+    fn push_forbidden_result(&self, file: &str, what: &str, lines: &[usize], results: &mut Vec<ValidationResult>) {
+        if lines.is_empty() {
+            results.push(ValidationResult::success(format!(
+                "{} does not contain {}",
+                file, what
+            )));
+            return;
+        }
+
+        let mut lines = lines.to_vec();
+        lines.dedup();
+        let line_list = lines
+            .iter()
+            .map(|l| l.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let label = if lines.len() == 1 { "line" } else { "lines" };
+        results.push(ValidationResult::error(
+            format!("{} contains {} ({} {})", file, what, label, line_list),
+            Some(format!("Remove {} from {}", what, file)),
+        ));
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parses a JSONPath-like selector (`$.engines.node`, `services.web.ports[0]`,
+/// `scripts["pre-commit"]`) into its path segments.
+fn parse_selector(selector: &str) -> std::result::Result<Vec<Segment>, String> {
+    let selector = selector.strip_prefix('$').unwrap_or(selector);
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut chars = selector.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                if !current.is_empty() {
+                    segments.push(Segment::Key(std::mem::take(&mut current)));
+                }
+            }
+            '[' => {
+                if !current.is_empty() {
+                    segments.push(Segment::Key(std::mem::take(&mut current)));
+                }
+                let mut inner = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        closed = true;
+                        break;
+                    }
+                    inner.push(c);
+                }
+                if !closed {
+                    return Err(format!("Missing ']' in selector {}", selector));
+                }
+                let inner = inner.trim();
+                let quoted = inner
+                    .strip_prefix('"')
+                    .and_then(|s| s.strip_suffix('"'))
+                    .or_else(|| inner.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')));
+                if let Some(key) = quoted {
+                    segments.push(Segment::Key(key.to_string()));
+                } else if let Ok(index) = inner.parse::<usize>() {
+                    segments.push(Segment::Index(index));
+                } else {
+                    return Err(format!("Invalid index '{}' in selector {}", inner, selector));
                 }
             }
+            _ => current.push(c),
+        }
+    }
+
+    if !current.is_empty() {
+        segments.push(Segment::Key(current));
+    }
+
+    if segments.is_empty() {
+        return Err("Selector must not be empty".to_string());
+    }
+
+    Ok(segments)
+}
+
+fn select<'a>(value: &'a Value, segments: &[Segment]) -> Option<&'a Value> {
+    segments.iter().try_fold(value, |value, segment| match (segment, value) {
+        (Segment::Key(key), Value::Object(map)) => map.get(key),
+        (Segment::Key(key), Value::Array(items)) => items.get(key.parse::<usize>().ok()?),
+        (Segment::Index(index), Value::Array(items)) => items.get(*index),
+        _ => None,
+    })
+}
+
+fn parse_document(path: &Path, format: FileFormat) -> Result<Value> {
+    let content = fs::read_to_string(path)?;
+    let document = match format {
+        FileFormat::Json => serde_json::from_str(&content)?,
+        FileFormat::Yaml => serde_yaml::from_str(&content)?,
+        FileFormat::Toml => toml::from_str(&content)?,
+    };
+    Ok(document)
+}
+
+fn format_name(format: FileFormat) -> &'static str {
+    match format {
+        FileFormat::Json => "JSON",
+        FileFormat::Yaml => "YAML",
+        FileFormat::Toml => "TOML",
+    }
+}
+
+/// Renders scalars without JSON quoting so they read naturally in messages.
+pub(crate) fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+pub(crate) fn values_equal(actual: &Value, expected: &Value) -> bool {
+    if actual == expected {
+        return true;
+    }
+    // YAML configs often quote numbers and booleans, so compare scalars by their text
+    match (actual, expected) {
+        (Value::Array(_) | Value::Object(_), _) | (_, Value::Array(_) | Value::Object(_)) => false,
+        _ => display_value(actual) == display_value(expected),
+    }
+}
+
+/// Compares a file's uid/gid against an expected user or group given by name or id,
+/// resolving names through the `/etc/passwd` or `/etc/group` database.
+#[cfg(unix)]
+fn check_id(
+    file: &str,
+    kind: &str,
+    expected: &str,
+    actual_id: u32,
+    database: &str,
+    command: &str,
+    results: &mut Vec<ValidationResult>,
+) {
+    let expected_id = match expected.parse::<u32>().ok().or_else(|| lookup_id(database, expected)) {
+        Some(id) => id,
+        None => {
+            results.push(ValidationResult::error(
+                format!("Unknown {} '{}' in ownership check for {}", kind, expected, file),
+                Some(format!("Ensure {} '{}' exists on this system", kind, expected)),
+            ));
+            return;
+        }
+    };
+
+    let actual = lookup_name(database, actual_id).unwrap_or_else(|| actual_id.to_string());
+    if actual_id == expected_id {
+        results.push(ValidationResult::success(format!(
+            "{} belongs to {} {}",
+            file, kind, actual
+        )));
+    } else {
+        results.push(ValidationResult::error(
+            format!("{} belongs to {} {}, but {} is required", file, kind, actual, expected),
+            Some(format!("Run 'sudo {} {} {}' to fix", command, expected, file)),
+        ));
+    }
+}
+
+/// Iterates `name:password:id:...` entries of a passwd-style database.
+#[cfg(unix)]
+fn database_entries(database: &str) -> Vec<(String, u32)> {
+    fs::read_to_string(database)
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let id = fields.nth(1)?.parse().ok()?;
+            Some((name.to_string(), id))
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+fn lookup_id(database: &str, name: &str) -> Option<u32> {
+    database_entries(database)
+        .into_iter()
+        .find(|(entry, _)| entry == name)
+        .map(|(_, id)| id)
+}
+
+#[cfg(unix)]
+fn lookup_name(database: &str, id: u32) -> Option<String> {
+    database_entries(database)
+        .into_iter()
+        .find(|(_, entry)| *entry == id)
+        .map(|(name, _)| name)
+}
+
+/// Returns true if a link whose target is `actual` resolves to `expected`,
+/// either literally or after resolving both relative to the link's directory.
+fn link_points_to(link: &Path, actual: &Path, expected: &Path) -> bool {
+    if actual == expected {
+        return true;
+    }
+    let base = link.parent().unwrap_or_else(|| Path::new(""));
+    match (base.join(actual).canonicalize(), base.join(expected).canonicalize()) {
+        (Ok(actual), Ok(expected)) => actual == expected,
+        _ => false,
+    }
+}
+
+fn hash_file<D: Digest + io::Write>(path: &Path) -> io::Result<String> {
+    let mut hasher = D::new();
+    io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+fn check_digest(
+    path: &Path,
+    algorithm: &str,
+    expected: &str,
+    actual: io::Result<String>,
+    results: &mut Vec<ValidationResult>,
+) {
+    let file = path.display();
+    match actual {
+        Ok(actual) if actual.eq_ignore_ascii_case(expected.trim()) => {
+            results.push(ValidationResult::success(format!(
+                "{} {} checksum matches",
+                file, algorithm
+            )));
+        }
+        Ok(actual) => results.push(ValidationResult::error(
+            format!("{} {} checksum mismatch (expected {}, got {})", file, algorithm, expected.trim(), actual),
+            Some(format!("Restore {} from a trusted source", file)),
+        )),
+        Err(e) => results.push(ValidationResult::error(
+            format!("Failed to compute {} checksum of {}", algorithm, file),
+            Some(format!("Error: {}", e)),
+        )),
+    }
+}
+
+/// Returns true if the path contains glob metacharacters.
+fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
+/// Byte offsets at which each line of `content` starts.
+fn line_starts(content: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(offset, _)| offset + 1))
+        .collect()
+}
+
+/// Returns the 1-based line number of a byte offset, given the `line_starts` of its content.
+fn line_number(line_starts: &[usize], offset: usize) -> usize {
+    line_starts.partition_point(|&start| start <= offset)
+}
+
+impl Validator for FileValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        match self.expand_paths() {
+            Ok(expanded) => Ok(expanded.validate_expanded()),
+            Err((path, e)) => Ok(vec![ValidationResult::error(
+                format!("Invalid file path '{}' in config: {}", path, e),
+                Some(e.suggestion()),
+            )]),
+        }
+    }
+}
+
+impl FileValidator {
+    /// Returns a validator whose paths have `~` and environment variables expanded.
+    fn expand_paths(&self) -> std::result::Result<Self, (String, ExpandError)> {
+        let expand = |path: &String| expand_path(path).map_err(|e| (path.clone(), e));
+        let mut check = self.check.clone();
+        check.path = expand(&check.path)?;
+        check.target = check.target.as_ref().map(expand).transpose()?;
+        check.newer_than = check.newer_than.as_ref().map(expand).transpose()?;
+        Ok(Self { check })
+    }
+
+    fn validate_expanded(&self) -> Vec<ValidationResult> {
+        let mut results = Vec::new();
+
+        let path = Path::new(&self.check.path);
+
+        // A path that exists as written, like pages/[id].tsx, is never treated as a glob
+        if is_glob(&self.check.path) && !path.exists() && !path.is_symlink() {
+            self.validate_glob(&mut results);
+            return results;
+        }
+
+        // `exists` follows symlinks, so dangling links are handled by `check_existing`
+        if path.exists() || path.is_symlink() {
+            self.check_existing(path, &mut results);
         } else {
             let item_type = if self.check.is_directory { "Directory" } else { "File" };
             if self.check.required {
@@ -83,6 +968,347 @@ impl Validator for FileValidator {
             }
         }
 
-        Ok(results)
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn validate_contents(content: &str, check: FileCheck) -> Vec<ValidationResult> {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "{}", content).unwrap();
+        let check = FileCheck {
+            path: file.path().to_str().unwrap().to_string(),
+            required: true,
+            ..check
+        };
+        FileValidator::new(check).validate().unwrap()
+    }
+
+    #[test]
+    fn test_line_number() {
+        let line_starts = line_starts("a\nb\nc");
+        assert_eq!(line_starts, vec![0, 2, 4]);
+        assert_eq!(line_number(&line_starts, 0), 1);
+        assert_eq!(line_number(&line_starts, 1), 1);
+        assert_eq!(line_number(&line_starts, 2), 2);
+        assert_eq!(line_number(&line_starts, 4), 3);
+    }
+
+    #[test]
+    fn test_content_checks() {
+        let content = "default: &default\n  adapter: postgresql\nDEBUG=true\nDEBUG=true\n";
+        let results = validate_contents(
+            content,
+            FileCheck {
+                contains: vec!["adapter: postgresql".to_string(), "registry=".to_string()],
+                not_contains: vec!["password".to_string()],
+                matches: vec!["^  adapter: \\w+$".to_string()],
+                not_matches: vec!["^DEBUG=true$".to_string()],
+                ..Default::default()
+            },
+        );
+
+        let messages: Vec<&str> = results.iter().map(|r| r.message.as_str()).collect();
+        assert!(messages.iter().any(|m| m.ends_with("contains 'adapter: postgresql' (line 2)")));
+        assert!(messages.iter().any(|m| m.ends_with("does not contain 'registry='")));
+        assert!(messages.iter().any(|m| m.ends_with("does not contain 'password'")));
+        assert!(messages.iter().any(|m| m.contains("matches pattern ^  adapter: \\w+$ (line 2)")));
+        assert!(messages.iter().any(|m| m.ends_with("contains pattern ^DEBUG=true$ (lines 3, 4)")));
+    }
+
+    #[test]
+    fn test_parse_selector() {
+        assert_eq!(
+            parse_selector("$.engines.node").unwrap(),
+            vec![Segment::Key("engines".to_string()), Segment::Key("node".to_string())]
+        );
+        assert_eq!(
+            parse_selector("services.web.ports[0]").unwrap(),
+            vec![
+                Segment::Key("services".to_string()),
+                Segment::Key("web".to_string()),
+                Segment::Key("ports".to_string()),
+                Segment::Index(0),
+            ]
+        );
+        assert_eq!(
+            parse_selector("scripts[\"pre.commit\"]").unwrap(),
+            vec![Segment::Key("scripts".to_string()), Segment::Key("pre.commit".to_string())]
+        );
+        assert!(parse_selector("ports[x]").is_err());
+        assert!(parse_selector("ports[0").is_err());
+        assert!(parse_selector("$").is_err());
+    }
+
+    #[test]
+    fn test_key_checks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("package.json");
+        fs::write(
+            &path,
+            r#"{"engines": {"node": "20.11.1"}, "private": true, "workspaces": ["a", "b"]}"#,
+        )
+        .unwrap();
+
+        let key = |key: &str| KeyCheck { key: key.to_string(), ..Default::default() };
+        let check = FileCheck {
+            path: path.to_str().unwrap().to_string(),
+            required: true,
+            keys: vec![
+                KeyCheck { semver: Some(">=18".to_string()), ..key("engines.node") },
+                KeyCheck { equals: Some(serde_json::json!("true")), ..key("private") },
+                KeyCheck { matches: Some("^b$".to_string()), ..key("workspaces[1]") },
+                KeyCheck { exists: Some(false), ..key("engines.npm") },
+                key("engines.yarn"),
+                KeyCheck { semver: Some(">=21".to_string()), ..key("engines.node") },
+            ],
+            ..Default::default()
+        };
+        let results = FileValidator::new(check).validate().unwrap();
+        let statuses: Vec<&ValidationStatus> = results.iter().skip(1).map(|r| &r.status).collect();
+
+        assert!(matches!(statuses[..], [
+            ValidationStatus::Success,
+            ValidationStatus::Success,
+            ValidationStatus::Success,
+            ValidationStatus::Success,
+            ValidationStatus::Error,
+            ValidationStatus::Error,
+        ]));
+        assert!(results[1].message.ends_with("engines.node = 20.11.1"));
+        assert!(results[5].message.ends_with("engines.yarn not found"));
+    }
+
+    #[test]
+    fn test_glob_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("001_init.sql"), "CREATE TABLE users;").unwrap();
+        fs::write(dir.path().join("002_posts.sql"), "CREATE TABLE posts;").unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        let glob_check = |pattern: &str, check: FileCheck| {
+            let check = FileCheck {
+                path: format!("{}/{}", root, pattern),
+                required: true,
+                ..check
+            };
+            FileValidator::new(check).validate().unwrap()
+        };
+
+        let results = glob_check("*.sql", FileCheck::default());
+        assert!(matches!(results[0].status, ValidationStatus::Success));
+        assert!(results[0].message.ends_with("matched 2 file(s)"));
+
+        let results = glob_check("**/*.pem", FileCheck { max_count: Some(0), ..Default::default() });
+        assert!(matches!(results[0].status, ValidationStatus::Success));
+
+        let results = glob_check("*.sql", FileCheck { max_count: Some(1), ..Default::default() });
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+        assert!(results[0].message.ends_with("expected at most 1"));
+
+        let results = glob_check("*.sql", FileCheck { min_count: Some(3), ..Default::default() });
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+
+        let results = glob_check(
+            "*.sql",
+            FileCheck { contains: vec!["users".to_string()], ..Default::default() },
+        );
+        assert_eq!(results.len(), 2);
+        assert!(matches!(results[1].status, ValidationStatus::Error));
+        assert!(results[1].message.contains("002_posts.sql does not contain 'users'"));
+
+        // Directories don't count as file matches or go through content checks
+        fs::create_dir(dir.path().join("003_archive.sql")).unwrap();
+        let results = glob_check("*.sql", FileCheck { contains: vec!["TABLE".to_string()], ..Default::default() });
+        assert!(results[0].message.ends_with("matched 2 file(s)"));
+        assert!(results.iter().all(|r| matches!(r.status, ValidationStatus::Success)));
+
+        // Paths that exist as written are checked literally
+        fs::write(dir.path().join("[id].tsx"), "export default Page;").unwrap();
+        let results = glob_check("[id].tsx", FileCheck { contains: vec!["Page".to_string()], ..Default::default() });
+        assert_eq!(results[0].message, format!("{}/[id].tsx exists", root));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_permission_masks() {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::set_permissions(file.path(), fs::Permissions::from_mode(0o400)).unwrap();
+        let uid = file.path().metadata().unwrap().uid();
+
+        let validate = |check: FileCheck| {
+            let check = FileCheck {
+                path: file.path().to_str().unwrap().to_string(),
+                required: true,
+                ..check
+            };
+            FileValidator::new(check).validate().unwrap()
+        };
+
+        let results = validate(FileCheck {
+            max_permissions: Some(0o600),
+            min_permissions: Some(0o400),
+            executable: Some(false),
+            owner: Some(uid.to_string()),
+            ..Default::default()
+        });
+        assert!(results.iter().all(|r| matches!(r.status, ValidationStatus::Success)));
+
+        let results = validate(FileCheck {
+            max_permissions: Some(0o200),
+            min_permissions: Some(0o600),
+            executable: Some(true),
+            owner: Some("no-such-user-envcheck".to_string()),
+            ..Default::default()
+        });
+        let errors: Vec<&ValidationResult> = results
+            .iter()
+            .filter(|r| matches!(r.status, ValidationStatus::Error))
+            .collect();
+        assert_eq!(errors.len(), 4);
+        assert!(errors[0].message.ends_with("has permissions 400, which exceed 200"));
+        assert_eq!(errors[0].suggestion.as_deref(), Some(format!("Run 'chmod 0 {}' to fix", file.path().display()).as_str()));
+        assert!(errors[1].message.ends_with("has permissions 400, which are missing 200"));
+        assert!(errors[2].message.ends_with("is not executable"));
+        assert!(errors[3].message.starts_with("Unknown user 'no-such-user-envcheck'"));
+    }
+
+    #[test]
+    fn test_integrity_checks() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock = dir.path().join("package-lock.json");
+        let fixture = dir.path().join("fixture.txt");
+        fs::write(&lock, "{}").unwrap();
+        fs::write(&fixture, "hello").unwrap();
+        let now = std::time::SystemTime::now();
+        let minute_ago = now - std::time::Duration::from_secs(60);
+        fs::File::options().write(true).open(&lock).unwrap().set_modified(minute_ago).unwrap();
+        fs::File::options().write(true).open(&fixture).unwrap().set_modified(now).unwrap();
+
+        let check = |check: FileCheck| {
+            let check = FileCheck {
+                path: fixture.to_str().unwrap().to_string(),
+                required: true,
+                ..check
+            };
+            FileValidator::new(check).validate().unwrap()
+        };
+
+        let results = check(FileCheck {
+            sha256: Some("2CF24DBA5FB0A30E26E83B2AC5B9E29E1B161E5C1FA7425E73043362938B9824".to_string()),
+            min_size: Some(5),
+            max_size: Some(5),
+            max_age: Some(std::time::Duration::from_secs(3600)),
+            newer_than: Some(lock.to_str().unwrap().to_string()),
+            ..Default::default()
+        });
+        assert!(results.iter().all(|r| matches!(r.status, ValidationStatus::Success)));
+
+        let results = check(FileCheck {
+            sha512: Some("00".to_string()),
+            max_size: Some(4),
+            ..Default::default()
+        });
+        let errors: Vec<&str> = results
+            .iter()
+            .filter(|r| matches!(r.status, ValidationStatus::Error))
+            .map(|r| r.message.as_str())
+            .collect();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].ends_with("is 5 B, larger than the maximum 4 B"));
+        assert!(errors[1].contains("SHA-512 checksum mismatch (expected 00, got 9b71d224"));
+
+        let results = FileValidator::new(FileCheck {
+            path: lock.to_str().unwrap().to_string(),
+            required: true,
+            newer_than: Some(fixture.to_str().unwrap().to_string()),
+            ..Default::default()
+        })
+        .validate()
+        .unwrap();
+        assert!(matches!(results[1].status, ValidationStatus::Error));
+        assert!(results[1].message.ends_with(&format!("is older than {}", fixture.display())));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_checks() {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join(".env.local");
+        let link = dir.path().join(".env");
+        let dangling = dir.path().join("current");
+        fs::write(&target, "PORT=3000").unwrap();
+        symlink(".env.local", &link).unwrap();
+        symlink("releases/missing", &dangling).unwrap();
+
+        let validate = |path: &Path, check: FileCheck| {
+            let check = FileCheck {
+                path: path.to_str().unwrap().to_string(),
+                required: true,
+                ..check
+            };
+            FileValidator::new(check).validate().unwrap()
+        };
+
+        let results = validate(&link, FileCheck {
+            symlink: Some(true),
+            target: Some(target.to_str().unwrap().to_string()),
+            ..Default::default()
+        });
+        assert!(results.iter().all(|r| matches!(r.status, ValidationStatus::Success)));
+        assert!(results[0].message.ends_with(".env is a symlink to .env.local"));
+
+        let results = validate(&link, FileCheck { target: Some(".env.example".to_string()), ..Default::default() });
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+        assert!(results[0].message.ends_with("links to .env.local, but .env.example is expected"));
+
+        let results = validate(&target, FileCheck { symlink: Some(true), ..Default::default() });
+        assert_eq!(results.len(), 1);
+        assert!(results[0].message.ends_with(".env.local is not a symlink"));
+
+        let results = validate(&dangling, FileCheck::default());
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+        assert!(results[0].message.ends_with("current is a broken symlink to releases/missing"));
+    }
+
+    #[test]
+    fn test_path_expansion() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("credentials"), "[default]").unwrap();
+        std::env::set_var("ENVCHECK_FILE_TEST_DIR", dir.path());
+        std::env::remove_var("ENVCHECK_FILE_TEST_UNSET");
+
+        let validate = |path: &str| {
+            FileValidator::new(FileCheck {
+                path: path.to_string(),
+                required: true,
+                ..Default::default()
+            })
+            .validate()
+            .unwrap()
+        };
+
+        let results = validate("${ENVCHECK_FILE_TEST_DIR}/credentials");
+        assert!(matches!(results[0].status, ValidationStatus::Success));
+        assert_eq!(results[0].message, format!("{}/credentials exists", dir.path().display()));
+
+        let results = validate("${ENVCHECK_FILE_TEST_UNSET:-/nonexistent}/credentials");
+        assert_eq!(results[0].message, "File /nonexistent/credentials does not exist");
+
+        let results = validate("${ENVCHECK_FILE_TEST_UNSET}/credentials");
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+        assert_eq!(
+            results[0].message,
+            "Invalid file path '${ENVCHECK_FILE_TEST_UNSET}/credentials' in config: unresolved variable ${ENVCHECK_FILE_TEST_UNSET}"
+        );
     }
 }
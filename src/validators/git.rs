@@ -0,0 +1,414 @@
+use crate::config::GitCheck;
+use crate::expand::expand_path;
+use crate::validators::{ValidationResult, Validator};
+use anyhow::Result;
+use std::path::Path;
+use std::process::Command;
+
+pub struct GitValidator {
+    check: GitCheck,
+}
+
+/// Runs git commands against one repository.
+struct Repo {
+    dir: String,
+}
+
+impl Repo {
+    /// Runs `git -C <dir> <args>`, returning trimmed stdout on success or stderr on failure.
+    fn git(&self, args: &[&str]) -> std::result::Result<String, String> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(args)
+            .output()
+            .map_err(|e| format!("Failed to run git: {}", e))?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        }
+    }
+
+    /// Reads a config value, treating an unset key as `None`.
+    fn config(&self, key: &str) -> Option<String> {
+        self.git(&["config", "--get", key])
+            .ok()
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Reads a path-valued config key, letting git expand a leading `~`.
+    fn config_path(&self, key: &str) -> Option<String> {
+        self.git(&["config", "--type=path", "--get", key])
+            .ok()
+            .filter(|value| !value.is_empty())
+    }
+}
+
+impl GitValidator {
+    pub fn new(check: GitCheck) -> Self {
+        Self { check }
+    }
+
+    fn check_hooks(&self, repo: &Repo, results: &mut Vec<ValidationResult>) {
+        let hooks_path = repo.config_path("core.hooksPath");
+        let hooks_dir = match &hooks_path {
+            // A relative core.hooksPath is resolved from the top of the worktree
+            Some(hooks_path) => repo
+                .git(&["rev-parse", "--show-toplevel"])
+                .map(|top| Path::new(&top).join(hooks_path)),
+            None => repo
+                .git(&["rev-parse", "--absolute-git-dir"])
+                .map(|git_dir| Path::new(&git_dir).join("hooks")),
+        };
+        let hooks_dir = match hooks_dir {
+            Ok(hooks_dir) => hooks_dir,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    "Could not locate the git hooks directory".to_string(),
+                    Some(format!("Error: {}", e)),
+                ));
+                return;
+            }
+        };
+
+        for hook in &self.check.hooks {
+            let hook_path = hooks_dir.join(hook);
+            if !hook_path.is_file() {
+                let suggestion = match &hooks_path {
+                    Some(_) => format!("Add {} or fix core.hooksPath", hook_path.display()),
+                    None => "Install the project's hooks (e.g. `pre-commit install`) or run `git config core.hooksPath <dir>`".to_string(),
+                };
+                results.push(ValidationResult::error(
+                    format!("Git hook {} is not installed", hook),
+                    Some(suggestion),
+                ));
+            } else if !is_executable(&hook_path) {
+                results.push(ValidationResult::error(
+                    format!("Git hook {} is not executable", hook),
+                    Some(format!("Run `chmod +x {}`", hook_path.display())),
+                ));
+            } else {
+                results.push(ValidationResult::success(format!("Git hook {} is installed", hook)));
+            }
+        }
+    }
+
+    fn check_user_email(&self, repo: &Repo, results: &mut Vec<ValidationResult>) {
+        let Some(email) = repo.config("user.email") else {
+            results.push(ValidationResult::error(
+                "Git user.email is not set".to_string(),
+                Some("Run `git config --global user.email \"you@example.com\"`".to_string()),
+            ));
+            return;
+        };
+
+        if let Some(pattern) = &self.check.user_email_matches {
+            let is_match = match regex::Regex::new(pattern) {
+                Ok(re) => re.is_match(&email),
+                Err(_) => email.contains(pattern.as_str()), // Fallback to substring if regex is invalid
+            };
+            if !is_match {
+                results.push(ValidationResult::error(
+                    format!("Git user.email {} does not match '{}'", email, pattern),
+                    Some("Run `git config user.email <your work address>` in this repository".to_string()),
+                ));
+                return;
+            }
+        }
+        results.push(ValidationResult::success(format!("Git user.email is {}", email)));
+    }
+
+    fn check_submodules(&self, repo: &Repo, results: &mut Vec<ValidationResult>) {
+        let status = match repo.git(&["submodule", "status", "--recursive"]) {
+            Ok(status) => status,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    "Failed to read submodule status".to_string(),
+                    Some(format!("Error: {}", e)),
+                ));
+                return;
+            }
+        };
+
+        let submodules = parse_submodule_status(&status);
+        let suggestion = Some("Run `git submodule update --init --recursive`".to_string());
+        let mut ok = true;
+        for (state, path) in &submodules {
+            let problem = match state {
+                '-' => "is not initialized",
+                '+' => "is not at the recorded commit",
+                'U' => "has merge conflicts",
+                _ => continue,
+            };
+            ok = false;
+            results.push(ValidationResult::error(
+                format!("Submodule {} {}", path, problem),
+                suggestion.clone(),
+            ));
+        }
+        if ok {
+            results.push(ValidationResult::success(format!(
+                "{} submodule(s) initialized and up to date",
+                submodules.len()
+            )));
+        }
+    }
+
+    fn check_lfs(&self, repo: &Repo, results: &mut Vec<ValidationResult>) {
+        let files = match repo.git(&["lfs", "ls-files"]) {
+            Ok(files) => files,
+            Err(e) if e.contains("not a git command") => {
+                results.push(ValidationResult::error(
+                    "Git LFS is not installed".to_string(),
+                    Some("Install Git LFS (https://git-lfs.com), then run `git lfs install && git lfs pull`".to_string()),
+                ));
+                return;
+            }
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    "Failed to list Git LFS files".to_string(),
+                    Some(format!("Error: {}", e)),
+                ));
+                return;
+            }
+        };
+
+        let missing = parse_lfs_missing(&files);
+        if missing.is_empty() {
+            results.push(ValidationResult::success("Git LFS objects are pulled".to_string()));
+        } else {
+            results.push(ValidationResult::error(
+                format!(
+                    "{} Git LFS object(s) not pulled: {}",
+                    missing.len(),
+                    missing.join(", ")
+                ),
+                Some("Run `git lfs pull`".to_string()),
+            ));
+        }
+    }
+
+    fn check_branch(&self, repo: &Repo, results: &mut Vec<ValidationResult>) {
+        let allowed = self.check.branch.join(" or ");
+        let branch = match repo.git(&["symbolic-ref", "--quiet", "--short", "HEAD"]) {
+            Ok(branch) => branch,
+            Err(_) => {
+                results.push(ValidationResult::error(
+                    "HEAD is detached".to_string(),
+                    Some(format!("Check out {}", allowed)),
+                ));
+                return;
+            }
+        };
+
+        let is_allowed = self.check.branch.iter().any(|pattern| match glob::Pattern::new(pattern) {
+            Ok(pattern) => pattern.matches(&branch),
+            Err(_) => *pattern == branch,
+        });
+        if is_allowed {
+            results.push(ValidationResult::success(format!("On branch {}", branch)));
+        } else {
+            results.push(ValidationResult::error(
+                format!("On branch {}, expected {}", branch, allowed),
+                Some(format!("Run `git checkout {}`", self.check.branch[0])),
+            ));
+        }
+    }
+
+    fn check_clean(&self, repo: &Repo, results: &mut Vec<ValidationResult>) {
+        match repo.git(&["status", "--porcelain"]) {
+            Ok(status) if status.is_empty() => {
+                results.push(ValidationResult::success("Git worktree is clean".to_string()));
+            }
+            Ok(status) => results.push(ValidationResult::error(
+                format!("Git worktree has {} uncommitted change(s)", status.lines().count()),
+                Some("Commit or stash your changes (`git stash`)".to_string()),
+            )),
+            Err(e) => results.push(ValidationResult::error(
+                "Failed to read git status".to_string(),
+                Some(format!("Error: {}", e)),
+            )),
+        }
+    }
+}
+
+impl Validator for GitValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        let mut results = Vec::new();
+
+        let dir = match expand_path(self.check.path.as_deref().unwrap_or(".")) {
+            Ok(dir) => dir,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Invalid git path in config: {}", e),
+                    Some(e.suggestion()),
+                ));
+                return Ok(results);
+            }
+        };
+
+        if which::which("git").is_err() {
+            results.push(ValidationResult::error(
+                "git not found".to_string(),
+                Some("Install git to check the repository".to_string()),
+            ));
+            return Ok(results);
+        }
+
+        let repo = Repo { dir };
+        if repo.git(&["rev-parse", "--git-dir"]).is_err() {
+            results.push(ValidationResult::error(
+                format!("{} is not a git repository", repo.dir),
+                Some("Clone the repository or run `git init`".to_string()),
+            ));
+            return Ok(results);
+        }
+
+        if !self.check.hooks.is_empty() {
+            self.check_hooks(&repo, &mut results);
+        }
+        if self.check.user_email || self.check.user_email_matches.is_some() {
+            self.check_user_email(&repo, &mut results);
+        }
+        if self.check.submodules {
+            self.check_submodules(&repo, &mut results);
+        }
+        if self.check.lfs {
+            self.check_lfs(&repo, &mut results);
+        }
+        if !self.check.branch.is_empty() {
+            self.check_branch(&repo, &mut results);
+        }
+        if self.check.clean {
+            self.check_clean(&repo, &mut results);
+        }
+
+        if results.is_empty() {
+            results.push(ValidationResult::success(format!("{} is a git repository", repo.dir)));
+        }
+        Ok(results)
+    }
+}
+
+/// Parses `git submodule status` lines like "-3f2a... vendor/lib" into
+/// their state prefix (' ', '-', '+' or 'U') and path.
+fn parse_submodule_status(status: &str) -> Vec<(char, String)> {
+    status
+        .lines()
+        .filter_map(|line| {
+            let state = line.chars().next()?;
+            let path = line[state.len_utf8()..].split_whitespace().nth(1)?;
+            Some((state, path.to_string()))
+        })
+        .collect()
+}
+
+/// Returns the files `git lfs ls-files` lists as pointers ("oid - path")
+/// rather than downloaded objects ("oid * path").
+fn parse_lfs_missing(files: &str) -> Vec<String> {
+    files
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, ' ');
+            let (_, marker, path) = (parts.next()?, parts.next()?, parts.next()?);
+            (marker == "-").then(|| path.to_string())
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|metadata| metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validators::ValidationStatus;
+    use std::fs;
+
+    #[test]
+    fn test_parse_submodule_status() {
+        let status = " 3f2a1b0 vendor/ok (v1.0)\n-9c8d7e6 vendor/missing\n+1a2b3c4 vendor/moved (heads/main)";
+        assert_eq!(
+            parse_submodule_status(status),
+            vec![
+                (' ', "vendor/ok".to_string()),
+                ('-', "vendor/missing".to_string()),
+                ('+', "vendor/moved".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_lfs_missing() {
+        let files = "4d7a214614 * assets/logo.png\n8c1d2e3f4a - assets/video file.mp4\n";
+        assert_eq!(parse_lfs_missing(files), vec!["assets/video file.mp4"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_repository_state() {
+        if which::which("git").is_err() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repo { dir: dir.path().to_str().unwrap().to_string() };
+        repo.git(&["init", "--quiet", "--initial-branch=main"]).unwrap();
+        repo.git(&["config", "user.email", "dev@ourcompany.com"]).unwrap();
+
+        let check = GitCheck {
+            path: Some(repo.dir.clone()),
+            hooks: vec!["pre-commit".to_string()],
+            user_email_matches: Some(r"@ourcompany\.com$".to_string()),
+            branch: vec!["main".to_string(), "release/*".to_string()],
+            clean: true,
+            ..Default::default()
+        };
+        let results = GitValidator::new(check.clone()).validate().unwrap();
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].message, "Git hook pre-commit is not installed");
+        assert!(matches!(results[1].status, ValidationStatus::Success));
+        assert_eq!(results[2].message, "On branch main");
+        assert_eq!(results[3].message, "Git worktree is clean");
+
+        let hook = dir.path().join(".git/hooks/pre-commit");
+        fs::write(&hook, "#!/bin/sh\n").unwrap();
+        fs::write(dir.path().join("notes.txt"), "draft").unwrap();
+        repo.git(&["checkout", "--quiet", "-b", "feature/x"]).unwrap();
+
+        let results = GitValidator::new(check.clone()).validate().unwrap();
+        assert_eq!(results[0].message, "Git hook pre-commit is not executable");
+        assert_eq!(results[2].message, "On branch feature/x, expected main or release/*");
+        assert_eq!(results[3].message, "Git worktree has 1 uncommitted change(s)");
+
+        if let Ok(home) = std::env::var("HOME") {
+            repo.git(&["config", "core.hooksPath", "~/.envcheck-missing-hooks"]).unwrap();
+            let results = GitValidator::new(check.clone()).validate().unwrap();
+            assert_eq!(
+                results[0].suggestion,
+                Some(format!("Add {}/.envcheck-missing-hooks/pre-commit or fix core.hooksPath", home.trim_end_matches('/')))
+            );
+            repo.git(&["config", "--unset", "core.hooksPath"]).unwrap();
+        }
+
+        let not_a_repo = tempfile::tempdir().unwrap();
+        let not_a_repo = not_a_repo.path().to_str().unwrap().to_string();
+        let results = GitValidator::new(GitCheck {
+            path: Some(not_a_repo.clone()),
+            ..Default::default()
+        })
+        .validate()
+        .unwrap();
+        assert_eq!(results[0].message, format!("{} is not a git repository", not_a_repo));
+    }
+}
@@ -0,0 +1,376 @@
+use crate::config::GoCheck;
+use crate::expand::expand_path;
+use crate::validators::{ValidationResult, Validator};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// How many missing modules are listed before "and N more".
+const MAX_LISTED: usize = 5;
+
+/// A `require` line from go.mod.
+#[derive(Debug, Clone, PartialEq)]
+struct Module {
+    path: String,
+    version: String,
+    indirect: bool,
+}
+
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.path, self.version)
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct GoMod {
+    requires: Vec<Module>,
+    /// Replaced module paths, mapped to the replacement module or `None` for a local directory
+    replaces: HashMap<String, Option<(String, String)>>,
+}
+
+impl GoMod {
+    /// The modules that must be downloaded, with replacements applied and local ones dropped.
+    fn downloads(&self) -> Vec<Module> {
+        self.requires
+            .iter()
+            .filter_map(|module| match self.replaces.get(&module.path) {
+                None => Some(module.clone()),
+                Some(None) => None,
+                Some(Some((path, version))) => Some(Module {
+                    path: path.clone(),
+                    version: version.clone(),
+                    indirect: module.indirect,
+                }),
+            })
+            .collect()
+    }
+}
+
+pub struct GoValidator {
+    check: GoCheck,
+}
+
+impl GoValidator {
+    pub fn new(check: GoCheck) -> Self {
+        Self { check }
+    }
+
+    /// Checks go.sum and either vendor/ or the module cache at `cache` against go.mod.
+    fn check_in(&self, dir: &Path, cache: Option<&Path>) -> Vec<ValidationResult> {
+        let mut results = Vec::new();
+        let Ok(go_mod) = fs::read_to_string(dir.join("go.mod")) else {
+            results.push(ValidationResult::error(
+                format!("No go.mod in {}", dir.display()),
+                Some("Set go.path to the directory of your Go module".to_string()),
+            ));
+            return results;
+        };
+        let modules = parse_go_mod(&go_mod).downloads();
+        if modules.is_empty() {
+            results.push(ValidationResult::success("go.mod has no external dependencies".to_string()));
+            return results;
+        }
+
+        let tidy = Some("Run `go mod tidy` and commit go.sum".to_string());
+        match fs::read_to_string(dir.join("go.sum")) {
+            Ok(go_sum) => {
+                let sums = parse_go_sum(&go_sum);
+                let unsummed: Vec<&Module> = modules
+                    .iter()
+                    .filter(|module| !sums.contains(&(module.path.clone(), module.version.clone())))
+                    .collect();
+                if unsummed.is_empty() {
+                    results.push(ValidationResult::success(format!(
+                        "go.sum covers go.mod ({} modules)",
+                        modules.len()
+                    )));
+                } else {
+                    results.push(ValidationResult::error(
+                        format!("go.sum is missing entries for {}", listed(&unsummed)),
+                        tidy,
+                    ));
+                }
+            }
+            Err(_) => results.push(ValidationResult::error(
+                format!("go.sum is missing in {}", dir.display()),
+                tidy,
+            )),
+        }
+
+        // With a vendor directory the build reads modules from it instead of the cache
+        if let Ok(vendored) = fs::read_to_string(dir.join("vendor/modules.txt")) {
+            let vendored = parse_modules_txt(&vendored);
+            let missing: Vec<&Module> = modules
+                .iter()
+                .filter(|module| !vendored.contains(&(module.path.clone(), module.version.clone())))
+                .collect();
+            if missing.is_empty() {
+                results.push(ValidationResult::success(format!(
+                    "vendor/modules.txt matches go.mod ({} modules)",
+                    modules.len()
+                )));
+            } else {
+                results.push(ValidationResult::error(
+                    format!("vendor/modules.txt is out of date with go.mod: {}", listed(&missing)),
+                    Some("Run `go mod vendor`".to_string()),
+                ));
+            }
+            return results;
+        }
+
+        let download = Some("Run `go mod download`".to_string());
+        let Some(cache) = cache.filter(|cache| cache.is_dir()) else {
+            let location = cache.map(|cache| format!(" at {}", cache.display())).unwrap_or_default();
+            results.push(ValidationResult::error(format!("Go module cache not found{}", location), download));
+            return results;
+        };
+        let missing: Vec<&Module> = modules.iter().filter(|module| !in_cache(cache, module)).collect();
+        if missing.is_empty() {
+            results.push(ValidationResult::success(format!(
+                "All {} modules from go.mod are in the module cache",
+                modules.len()
+            )));
+        } else {
+            results.push(ValidationResult::error(
+                format!(
+                    "{} of {} modules from go.mod are not in the module cache: {}",
+                    missing.len(),
+                    modules.len(),
+                    listed(&missing)
+                ),
+                download,
+            ));
+        }
+        results
+    }
+}
+
+impl Validator for GoValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        let dir = match expand_path(self.check.path.as_deref().unwrap_or(".")) {
+            Ok(dir) => PathBuf::from(dir),
+            Err(e) => {
+                return Ok(vec![ValidationResult::error(
+                    format!("Invalid go path in config: {}", e),
+                    Some(e.suggestion()),
+                )])
+            }
+        };
+        let cache = module_cache(&dir);
+        Ok(self.check_in(&dir, cache.as_deref()))
+    }
+}
+
+fn listed(modules: &[&Module]) -> String {
+    let names: Vec<String> = modules.iter().take(MAX_LISTED).map(|module| module.to_string()).collect();
+    match modules.len().saturating_sub(MAX_LISTED) {
+        0 => names.join(", "),
+        n => format!("{} (and {} more)", names.join(", "), n),
+    }
+}
+
+fn parse_go_mod(contents: &str) -> GoMod {
+    let mut go_mod = GoMod::default();
+    let mut block: Option<String> = None;
+    for line in contents.lines() {
+        let indirect = line.contains("// indirect");
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (directive, rest) = match &block {
+            Some(_) if line == ")" => {
+                block = None;
+                continue;
+            }
+            Some(directive) => (directive.clone(), line),
+            None => {
+                let (directive, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+                if rest.trim() == "(" {
+                    block = Some(directive.to_string());
+                    continue;
+                }
+                (directive.to_string(), rest.trim())
+            }
+        };
+
+        let unquote = |s: &str| s.trim_matches('"').to_string();
+        match directive.as_str() {
+            "require" => {
+                let mut fields = rest.split_whitespace();
+                if let (Some(path), Some(version)) = (fields.next(), fields.next()) {
+                    go_mod.requires.push(Module {
+                        path: unquote(path),
+                        version: version.to_string(),
+                        indirect,
+                    });
+                }
+            }
+            "replace" => {
+                let Some((old, new)) = rest.split_once("=>") else {
+                    continue;
+                };
+                let old = old.split_whitespace().next().map(unquote).unwrap_or_default();
+                let mut new = new.split_whitespace().map(unquote);
+                let replacement = match (new.next(), new.next()) {
+                    (Some(path), Some(version)) => Some((path, version)),
+                    // A replacement without a version is a local directory
+                    _ => None,
+                };
+                go_mod.replaces.insert(old, replacement);
+            }
+            _ => {}
+        }
+    }
+    go_mod
+}
+
+/// Module versions whose go.mod hash is recorded in go.sum.
+fn parse_go_sum(contents: &str) -> HashSet<(String, String)> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let (path, version) = (fields.next()?, fields.next()?);
+            let version = version.strip_suffix("/go.mod")?;
+            Some((path.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+/// Module versions listed as `# path version` in vendor/modules.txt.
+fn parse_modules_txt(contents: &str) -> HashSet<(String, String)> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.strip_prefix("# ")?.split_whitespace();
+            Some((fields.next()?.to_string(), fields.next()?.to_string()))
+        })
+        .collect()
+}
+
+/// Escapes a module path or version for the cache, which writes `A` as `!a`
+/// so paths stay unique on case-insensitive filesystems.
+fn escape(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if c.is_ascii_uppercase() {
+            escaped.push('!');
+            escaped.push(c.to_ascii_lowercase());
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// Whether a module is extracted in the cache or at least its zip was downloaded.
+fn in_cache(cache: &Path, module: &Module) -> bool {
+    let (path, version) = (escape(&module.path), escape(&module.version));
+    cache.join(format!("{}@{}", path, version)).is_dir()
+        || cache
+            .join("cache/download")
+            .join(&path)
+            .join("@v")
+            .join(format!("{}.zip", version))
+            .is_file()
+}
+
+/// Finds the module cache: GOMODCACHE, then `go env GOMODCACHE`, then GOPATH/pkg/mod.
+fn module_cache(dir: &Path) -> Option<PathBuf> {
+    if let Some(cache) = env::var_os("GOMODCACHE").filter(|cache| !cache.is_empty()) {
+        return Some(PathBuf::from(cache));
+    }
+    if let Ok(go) = which::which("go") {
+        if let Ok(output) = Command::new(go).args(["env", "GOMODCACHE"]).current_dir(dir).output() {
+            let cache = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if output.status.success() && !cache.is_empty() {
+                return Some(PathBuf::from(cache));
+            }
+        }
+    }
+    let gopath = match env::var_os("GOPATH") {
+        Some(gopath) => env::split_paths(&gopath).next()?,
+        None => PathBuf::from(expand_path("~/go").ok()?),
+    };
+    Some(gopath.join("pkg/mod"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validators::ValidationStatus;
+
+    const GO_MOD: &str = "\
+module example.com/api
+
+go 1.22
+
+require github.com/BurntSushi/toml v1.3.2
+
+require (
+\tgithub.com/lib/pq v1.10.9
+\tgolang.org/x/sys v0.18.0 // indirect
+\texample.com/shared v0.0.0
+)
+
+replace example.com/shared => ../shared
+";
+
+    #[test]
+    fn test_parse_go_mod() {
+        let go_mod = parse_go_mod(GO_MOD);
+        assert_eq!(go_mod.requires.len(), 4);
+        assert_eq!(go_mod.requires[0].to_string(), "github.com/BurntSushi/toml v1.3.2");
+        assert!(go_mod.requires[2].indirect && !go_mod.requires[1].indirect);
+
+        let downloads: Vec<String> = go_mod.downloads().iter().map(Module::to_string).collect();
+        assert_eq!(
+            downloads,
+            vec!["github.com/BurntSushi/toml v1.3.2", "github.com/lib/pq v1.10.9", "golang.org/x/sys v0.18.0"]
+        );
+        assert_eq!(escape("github.com/BurntSushi/toml"), "github.com/!burnt!sushi/toml");
+    }
+
+    #[test]
+    fn test_go_sum_and_cache() {
+        let project = tempfile::tempdir().unwrap();
+        fs::write(project.path().join("go.mod"), GO_MOD).unwrap();
+        fs::write(
+            project.path().join("go.sum"),
+            "github.com/BurntSushi/toml v1.3.2 h1:abc=\n\
+             github.com/BurntSushi/toml v1.3.2/go.mod h1:def=\n\
+             github.com/lib/pq v1.10.9/go.mod h1:ghi=\n",
+        )
+        .unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        fs::create_dir_all(cache.path().join("github.com/!burnt!sushi/toml@v1.3.2")).unwrap();
+        let download = cache.path().join("cache/download/github.com/lib/pq/@v");
+        fs::create_dir_all(&download).unwrap();
+        fs::write(download.join("v1.10.9.zip"), "").unwrap();
+
+        let validator = GoValidator::new(GoCheck::default());
+        let results = validator.check_in(project.path(), Some(cache.path()));
+        assert_eq!(results[0].message, "go.sum is missing entries for golang.org/x/sys v0.18.0");
+        assert!(matches!(results[1].status, ValidationStatus::Error));
+        assert_eq!(
+            results[1].message,
+            "1 of 3 modules from go.mod are not in the module cache: golang.org/x/sys v0.18.0"
+        );
+
+        fs::create_dir(project.path().join("vendor")).unwrap();
+        fs::write(
+            project.path().join("vendor/modules.txt"),
+            "# github.com/BurntSushi/toml v1.3.2\n## explicit\n# github.com/lib/pq v1.10.9\n## explicit\n\
+             # golang.org/x/sys v0.18.0\n## explicit\ngolang.org/x/sys/unix\n",
+        )
+        .unwrap();
+        let results = validator.check_in(project.path(), None);
+        assert_eq!(results[1].message, "vendor/modules.txt matches go.mod (3 modules)");
+    }
+}
@@ -0,0 +1,444 @@
+use crate::config::LibraryCheck;
+use crate::validators::os::{numeric_version, satisfies, Distro, Platform};
+use crate::validators::{ValidationResult, Validator};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::env;
+use std::env::consts;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const DEFAULT_PC_DIRS: &[&str] = &[
+    "/usr/local/lib/pkgconfig",
+    "/usr/local/share/pkgconfig",
+    "/usr/lib/pkgconfig",
+    "/usr/lib64/pkgconfig",
+    "/usr/share/pkgconfig",
+    "/opt/homebrew/lib/pkgconfig",
+];
+
+const DEFAULT_INCLUDE_DIRS: &[&str] = &["/usr/local/include", "/usr/include", "/opt/homebrew/include"];
+
+/// Libraries whose pkg-config name doesn't match their shared object or
+/// development packages: (pkg-config name, library, apt package, dnf package).
+const KNOWN_LIBRARIES: &[(&str, &str, &str, &str)] = &[
+    ("openssl", "libssl", "libssl-dev", "openssl-devel"),
+    ("libssl", "libssl", "libssl-dev", "openssl-devel"),
+    ("libcrypto", "libcrypto", "libssl-dev", "openssl-devel"),
+    ("libpq", "libpq", "libpq-dev", "libpq-devel"),
+    ("protobuf", "libprotobuf", "libprotobuf-dev", "protobuf-devel"),
+    ("zlib", "libz", "zlib1g-dev", "zlib-devel"),
+    ("sqlite3", "libsqlite3", "libsqlite3-dev", "sqlite-devel"),
+    ("libcurl", "libcurl", "libcurl4-openssl-dev", "libcurl-devel"),
+    ("libxml-2.0", "libxml2", "libxml2-dev", "libxml2-devel"),
+    ("liblzma", "liblzma", "liblzma-dev", "xz-devel"),
+    ("mysqlclient", "libmysqlclient", "libmysqlclient-dev", "mysql-devel"),
+    ("libudev", "libudev", "libudev-dev", "systemd-devel"),
+    ("dbus-1", "libdbus-1", "libdbus-1-dev", "dbus-devel"),
+    ("alsa", "libasound", "libasound2-dev", "alsa-lib-devel"),
+];
+
+/// Where to look for libraries: pkg-config directories, include directories
+/// and the output of `ldconfig -p`.
+#[derive(Debug, Default)]
+struct SearchPaths {
+    pc_dirs: Vec<PathBuf>,
+    include_dirs: Vec<PathBuf>,
+    ldconfig: String,
+    distro: Option<Distro>,
+}
+
+impl SearchPaths {
+    /// Mirrors pkg-config: `PKG_CONFIG_PATH` first, then `PKG_CONFIG_LIBDIR`
+    /// or the default directories.
+    fn system() -> Self {
+        let multiarch = format!("/usr/lib/{}-linux-gnu", consts::ARCH);
+
+        let mut pc_dirs: Vec<PathBuf> = env::var_os("PKG_CONFIG_PATH")
+            .map(|paths| env::split_paths(&paths).collect())
+            .unwrap_or_default();
+        match env::var_os("PKG_CONFIG_LIBDIR") {
+            Some(paths) => pc_dirs.extend(env::split_paths(&paths)),
+            None => {
+                pc_dirs.push(Path::new(&multiarch).join("pkgconfig"));
+                pc_dirs.extend(DEFAULT_PC_DIRS.iter().map(PathBuf::from));
+            }
+        }
+
+        let mut include_dirs: Vec<PathBuf> = ["CPATH", "C_INCLUDE_PATH"]
+            .iter()
+            .filter_map(env::var_os)
+            .flat_map(|paths| env::split_paths(&paths).collect::<Vec<_>>())
+            .collect();
+        include_dirs.extend(DEFAULT_INCLUDE_DIRS.iter().map(PathBuf::from));
+        include_dirs.push(PathBuf::from(format!("/usr/include/{}-linux-gnu", consts::ARCH)));
+
+        let ldconfig = ["ldconfig", "/sbin/ldconfig"]
+            .iter()
+            .find_map(|program| Command::new(program).arg("-p").output().ok())
+            .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+            .unwrap_or_default();
+
+        Self {
+            pc_dirs,
+            include_dirs,
+            ldconfig,
+            distro: Platform::detect().distro,
+        }
+    }
+}
+
+/// The fields of a `.pc` file we need, with variables expanded.
+#[derive(Debug, Default, PartialEq)]
+struct PkgConfig {
+    path: PathBuf,
+    version: Option<String>,
+    include_dirs: Vec<PathBuf>,
+}
+
+pub struct LibraryValidator {
+    check: LibraryCheck,
+}
+
+impl LibraryValidator {
+    pub fn new(check: LibraryCheck) -> Self {
+        Self { check }
+    }
+
+    fn check_in(&self, search: &SearchPaths) -> Vec<ValidationResult> {
+        let mut results = Vec::new();
+        let name = &self.check.name;
+        let (library, _, _) = packages(name);
+
+        let pc = search
+            .pc_dirs
+            .iter()
+            .map(|dir| dir.join(format!("{}.pc", name)))
+            .find(|path| path.is_file())
+            .and_then(|path| {
+                let contents = fs::read_to_string(&path).ok()?;
+                Some(parse_pc(&contents, &path))
+            });
+
+        match &pc {
+            Some(pc) => self.check_version(pc, search, &mut results),
+            None => match find_in_ldconfig(&search.ldconfig, &library) {
+                // Only the runtime library: linking needs the dev package's symlink and headers
+                Some((so, false)) => {
+                    results.push(self.failure(
+                        format!("{} development files not found (only the runtime library {} is installed)", name, so),
+                        install_hint(name, search.distro.as_ref()),
+                    ));
+                    return results;
+                }
+                Some((so, _)) if self.check.version.is_some() => results.push(ValidationResult::warning(
+                    format!("Cannot check the {} version: {} is installed but has no pkg-config file", name, so),
+                    Some(install_hint(name, search.distro.as_ref())),
+                )),
+                Some((so, _)) => results.push(ValidationResult::success(format!(
+                    "{} found at {} (no pkg-config file)",
+                    name, so
+                ))),
+                None => {
+                    results.push(self.failure(format!("{} not found", name), install_hint(name, search.distro.as_ref())));
+                    return results;
+                }
+            },
+        }
+
+        let mut include_dirs: Vec<&Path> = pc
+            .iter()
+            .flat_map(|pc| pc.include_dirs.iter().map(PathBuf::as_path))
+            .collect();
+        include_dirs.extend(search.include_dirs.iter().map(PathBuf::as_path));
+        for header in &self.check.headers {
+            match include_dirs.iter().map(|dir| dir.join(header)).find(|path| path.is_file()) {
+                Some(path) => results.push(ValidationResult::success(format!(
+                    "Header {} found at {}",
+                    header,
+                    path.display()
+                ))),
+                None => results.push(self.failure(
+                    format!("Header {} not found", header),
+                    install_hint(name, search.distro.as_ref()),
+                )),
+            }
+        }
+
+        results
+    }
+
+    fn check_version(&self, pc: &PkgConfig, search: &SearchPaths, results: &mut Vec<ValidationResult>) {
+        let name = &self.check.name;
+        let version = pc.version.as_deref().unwrap_or("unknown version");
+        let Some(requirement) = &self.check.version else {
+            results.push(ValidationResult::success(format!(
+                "{} {} found ({})",
+                name,
+                version,
+                pc.path.display()
+            )));
+            return;
+        };
+
+        let numeric = pc.version.as_deref().and_then(numeric_version);
+        if numeric.as_deref().is_some_and(|v| satisfies(v, requirement)) {
+            results.push(ValidationResult::success(format!(
+                "{} {} satisfies {}",
+                name, version, requirement
+            )));
+        } else {
+            results.push(self.failure(
+                format!("{} {} does not satisfy {}", name, version, requirement),
+                format!(
+                    "Upgrade {} to {} or point PKG_CONFIG_PATH at a newer install ({})",
+                    name,
+                    requirement,
+                    install_hint(name, search.distro.as_ref())
+                ),
+            ));
+        }
+    }
+
+    fn failure(&self, message: String, suggestion: String) -> ValidationResult {
+        if self.check.required {
+            ValidationResult::error(message, Some(suggestion))
+        } else {
+            ValidationResult::warning(format!("{} (optional)", message), Some(suggestion))
+        }
+    }
+}
+
+impl Validator for LibraryValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        Ok(self.check_in(&SearchPaths::system()))
+    }
+}
+
+/// Parses a `.pc` file: `name=value` lines define variables and `Key: value`
+/// lines define fields, both of which may reference `${variable}`.
+fn parse_pc(contents: &str, path: &Path) -> PkgConfig {
+    let mut variables = HashMap::new();
+    if let Some(dir) = path.parent() {
+        variables.insert("pcfiledir".to_string(), dir.display().to_string());
+    }
+    let mut pc = PkgConfig {
+        path: path.to_path_buf(),
+        ..Default::default()
+    };
+
+    for line in contents.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let Some(split) = line.find([':', '=']) else {
+            continue;
+        };
+        let (key, value) = (line[..split].trim(), expand(line[split + 1..].trim(), &variables));
+        if line.as_bytes()[split] == b'=' {
+            variables.insert(key.to_string(), value);
+            continue;
+        }
+        match key {
+            "Version" => pc.version = Some(value),
+            "Cflags" | "Cflags.private" => {
+                let mut args = value.split_whitespace();
+                while let Some(arg) = args.next() {
+                    match arg.strip_prefix("-I") {
+                        Some("") => pc.include_dirs.extend(args.next().map(PathBuf::from)),
+                        Some(dir) => pc.include_dirs.push(PathBuf::from(dir)),
+                        None => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(dir) = variables.get("includedir") {
+        pc.include_dirs.push(PathBuf::from(dir));
+    }
+    pc
+}
+
+/// Replaces `${name}` with the variable's value, leaving unknown names empty.
+fn expand(value: &str, variables: &HashMap<String, String>) -> String {
+    let mut expanded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        expanded.push_str(&rest[..start]);
+        if let Some(value) = variables.get(&rest[start + 2..start + end]) {
+            expanded.push_str(value);
+        }
+        rest = &rest[start + end + 1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Finds `library.so*` in `ldconfig -p` output, preferring the unversioned
+/// `library.so` symlink that the dev package installs for the linker.
+/// Returns the path and whether it is that symlink.
+fn find_in_ldconfig(output: &str, library: &str) -> Option<(String, bool)> {
+    let dev = format!("{}.so", library);
+    let runtime = format!("{}.so.", library);
+    let mut found = None;
+    for line in output.lines() {
+        let Some((soname, path)) = line.trim().split_once(" => ") else {
+            continue;
+        };
+        let soname = soname.split_whitespace().next().unwrap_or_default();
+        if soname == dev {
+            return Some((path.trim().to_string(), true));
+        }
+        if found.is_none() && soname.starts_with(&runtime) {
+            found = Some((path.trim().to_string(), false));
+        }
+    }
+    found
+}
+
+/// The shared library and apt and dnf development packages for a pkg-config name.
+fn packages(name: &str) -> (String, String, String) {
+    if let Some((_, library, apt, dnf)) = KNOWN_LIBRARIES.iter().find(|(known, ..)| *known == name) {
+        return (library.to_string(), apt.to_string(), dnf.to_string());
+    }
+    let library = if name.starts_with("lib") {
+        name.to_string()
+    } else {
+        format!("lib{}", name)
+    };
+    (library.clone(), format!("{}-dev", library), format!("{}-devel", name))
+}
+
+fn install_hint(name: &str, distro: Option<&Distro>) -> String {
+    let (_, apt, dnf) = packages(name);
+    let is = |family: &[&str]| {
+        distro.is_some_and(|distro| {
+            family
+                .iter()
+                .any(|id| distro.id == *id || distro.id_like.iter().any(|like| like == id))
+        })
+    };
+    if is(&["debian", "ubuntu"]) {
+        format!("Install it with `sudo apt install {}`", apt)
+    } else if is(&["fedora", "rhel", "centos"]) {
+        format!("Install it with `sudo dnf install {}`", dnf)
+    } else {
+        format!(
+            "Install the {} development package ({} on Debian/Ubuntu, {} on Fedora/RHEL) or add its .pc directory to PKG_CONFIG_PATH",
+            name, apt, dnf
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validators::ValidationStatus;
+
+    const OPENSSL_PC: &str = r#"prefix=/usr
+exec_prefix=${prefix}
+libdir=${exec_prefix}/lib/x86_64-linux-gnu
+includedir=${prefix}/include
+
+Name: OpenSSL
+Description: Secure Sockets Layer and cryptography libraries and tools
+Version: 3.0.13
+Requires: libssl libcrypto
+Cflags: -I${includedir}/openssl3 -I ${prefix}/local/include
+"#;
+
+    const LDCONFIG: &str = "1024 libs found in cache `/etc/ld.so.cache'
+\tlibssl.so.3 (libc6,x86-64) => /lib/x86_64-linux-gnu/libssl.so.3
+\tlibpq.so.5 (libc6,x86-64) => /lib/x86_64-linux-gnu/libpq.so.5
+\tlibz.so.1 (libc6,x86-64) => /lib/x86_64-linux-gnu/libz.so.1
+\tlibz.so (libc6,x86-64) => /lib/x86_64-linux-gnu/libz.so
+";
+
+    fn check(name: &str, version: Option<&str>, headers: &[&str]) -> LibraryCheck {
+        LibraryCheck {
+            name: name.to_string(),
+            version: version.map(str::to_string),
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            required: true,
+            when: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_pc() {
+        let pc = parse_pc(OPENSSL_PC, Path::new("/usr/lib/pkgconfig/openssl.pc"));
+        assert_eq!(pc.version.as_deref(), Some("3.0.13"));
+        assert_eq!(
+            pc.include_dirs,
+            vec![
+                PathBuf::from("/usr/include/openssl3"),
+                PathBuf::from("/usr/local/include"),
+                PathBuf::from("/usr/include"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_in_ldconfig() {
+        assert_eq!(
+            find_in_ldconfig(LDCONFIG, "libpq"),
+            Some(("/lib/x86_64-linux-gnu/libpq.so.5".to_string(), false))
+        );
+        assert_eq!(
+            find_in_ldconfig(LDCONFIG, "libz"),
+            Some(("/lib/x86_64-linux-gnu/libz.so".to_string(), true))
+        );
+        assert_eq!(find_in_ldconfig(LDCONFIG, "libprotobuf"), None);
+    }
+
+    #[test]
+    fn test_library_checks() {
+        let dir = tempfile::tempdir().unwrap();
+        let prefix = format!("prefix={}", dir.path().display());
+        fs::write(dir.path().join("openssl.pc"), OPENSSL_PC.replace("prefix=/usr", &prefix)).unwrap();
+        fs::create_dir_all(dir.path().join("include/openssl")).unwrap();
+        fs::write(dir.path().join("include/openssl/ssl.h"), "").unwrap();
+        let search = SearchPaths {
+            pc_dirs: vec![dir.path().to_path_buf()],
+            include_dirs: Vec::new(),
+            ldconfig: LDCONFIG.to_string(),
+            distro: Some(Distro {
+                id: "ubuntu".to_string(),
+                id_like: vec!["debian".to_string()],
+                ..Default::default()
+            }),
+        };
+
+        let results = LibraryValidator::new(check("openssl", Some(">=3"), &["openssl/ssl.h"])).check_in(&search);
+        assert!(results.iter().all(|r| matches!(r.status, ValidationStatus::Success)));
+        assert_eq!(results[0].message, "openssl 3.0.13 satisfies >=3");
+
+        let results = LibraryValidator::new(check("openssl", Some(">=3.1"), &["openssl/evp.h"])).check_in(&search);
+        assert_eq!(results[0].message, "openssl 3.0.13 does not satisfy >=3.1");
+        assert_eq!(results[1].message, "Header openssl/evp.h not found");
+        assert_eq!(results[1].suggestion.as_deref(), Some("Install it with `sudo apt install libssl-dev`"));
+
+        let results = LibraryValidator::new(check("libpq", None, &[])).check_in(&search);
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+        assert_eq!(
+            results[0].message,
+            "libpq development files not found (only the runtime library /lib/x86_64-linux-gnu/libpq.so.5 is installed)"
+        );
+        assert_eq!(results[0].suggestion.as_deref(), Some("Install it with `sudo apt install libpq-dev`"));
+
+        let results = LibraryValidator::new(check("zlib", None, &[])).check_in(&search);
+        assert_eq!(results[0].message, "zlib found at /lib/x86_64-linux-gnu/libz.so (no pkg-config file)");
+
+        let results = LibraryValidator::new(check("protobuf", None, &["google/protobuf/message.h"])).check_in(&search);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].message, "protobuf not found");
+        assert_eq!(
+            results[0].suggestion.as_deref(),
+            Some("Install it with `sudo apt install libprotobuf-dev`")
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use crate::config::LimitCheck;
+use crate::units::format_size;
+use crate::validators::{ValidationResult, Validator};
+use anyhow::Result;
+use std::io;
+
+const LIMIT_NAMES: &str = "nofile, nproc, memlock, stack or core";
+
+/// A resource limit we know how to read and describe.
+#[derive(Debug, Clone, Copy)]
+struct Limit {
+    name: &'static str,
+    description: &'static str,
+    /// Flag for the shell's `ulimit` builtin
+    flag: char,
+    /// Whether the limit is in bytes (`ulimit` takes KiB) rather than a count
+    bytes: bool,
+}
+
+const LIMITS: &[Limit] = &[
+    Limit { name: "nofile", description: "Open files limit", flag: 'n', bytes: false },
+    Limit { name: "nproc", description: "Process limit", flag: 'u', bytes: false },
+    Limit { name: "memlock", description: "Locked memory limit", flag: 'l', bytes: true },
+    Limit { name: "stack", description: "Stack size limit", flag: 's', bytes: true },
+    Limit { name: "core", description: "Core file size limit", flag: 'c', bytes: true },
+];
+
+impl Limit {
+    fn format(&self, value: u64) -> String {
+        match value {
+            u64::MAX => "unlimited".to_string(),
+            value if self.bytes => format_size(value),
+            value => value.to_string(),
+        }
+    }
+
+    /// The argument to pass to `ulimit`, which counts bytes in KiB.
+    fn ulimit_value(&self, value: u64) -> String {
+        match value {
+            u64::MAX => "unlimited".to_string(),
+            value if self.bytes => value.div_ceil(1024).to_string(),
+            value => value.to_string(),
+        }
+    }
+}
+
+pub struct LimitValidator {
+    check: LimitCheck,
+}
+
+impl LimitValidator {
+    pub fn new(check: LimitCheck) -> Self {
+        Self { check }
+    }
+
+    /// Compares the soft limit against the check, using the hard limit to
+    /// tell whether `ulimit` alone can fix it.
+    fn check_limit(&self, limit: &Limit, soft: u64, hard: u64) -> ValidationResult {
+        let label = format!("{} ({})", limit.description, limit.name);
+        let (bound, expected) = match (self.check.min, self.check.max) {
+            (Some(min), _) if soft < min => ("at least", min),
+            (_, Some(max)) if soft > max => ("at most", max),
+            _ => return ValidationResult::success(format!("{} is {}", label, limit.format(soft))),
+        };
+
+        let command = format!("ulimit -{} {}", limit.flag, limit.ulimit_value(expected));
+        let suggestion = if bound == "at least" && hard < expected {
+            format!(
+                "The hard limit is {}; raise it with `* hard {} {}` in /etc/security/limits.conf \
+                 (or Limit{}= for systemd services), log in again and run `{}`",
+                limit.format(hard),
+                limit.name,
+                limit.ulimit_value(expected),
+                limit.name.to_uppercase(),
+                command
+            )
+        } else {
+            format!("Run `{}` or add it to your shell profile", command)
+        };
+
+        ValidationResult::error(
+            format!("{} is {}, need {} {}", label, limit.format(soft), bound, limit.format(expected)),
+            Some(suggestion),
+        )
+    }
+}
+
+impl Validator for LimitValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        let Some(limit) = LIMITS.iter().find(|limit| limit.name == self.check.name) else {
+            return Ok(vec![ValidationResult::error(
+                format!("Unknown limit '{}' in config", self.check.name),
+                Some(format!("Use one of {}", LIMIT_NAMES)),
+            )]);
+        };
+
+        let result = match get_limit(limit.name) {
+            Ok((soft, hard)) => self.check_limit(limit, soft, hard),
+            Err(e) => ValidationResult::warning(
+                format!("Could not read the {} limit", limit.name),
+                Some(format!("Error: {}", e)),
+            ),
+        };
+        Ok(vec![result])
+    }
+}
+
+/// Returns the soft and hard limits, with `u64::MAX` meaning unlimited.
+#[cfg(unix)]
+fn get_limit(name: &str) -> io::Result<(u64, u64)> {
+    let resource = match name {
+        "nofile" => libc::RLIMIT_NOFILE,
+        "nproc" => libc::RLIMIT_NPROC,
+        "memlock" => libc::RLIMIT_MEMLOCK,
+        "stack" => libc::RLIMIT_STACK,
+        "core" => libc::RLIMIT_CORE,
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, "unknown limit")),
+    };
+    // SAFETY: getrlimit only writes into the struct we pass it
+    let mut limit: libc::rlimit = unsafe { std::mem::zeroed() };
+    if unsafe { libc::getrlimit(resource, &mut limit) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // rlim_t is u64 on most platforms but not all
+    #[allow(clippy::unnecessary_cast)]
+    let value = |v: libc::rlim_t| if v == libc::RLIM_INFINITY { u64::MAX } else { v as u64 };
+    Ok((value(limit.rlim_cur), value(limit.rlim_max)))
+}
+
+#[cfg(not(unix))]
+fn get_limit(_name: &str) -> io::Result<(u64, u64)> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "resource limits are only checked on Unix",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validators::ValidationStatus;
+
+    fn check(name: &str, min: Option<u64>, max: Option<u64>) -> LimitCheck {
+        LimitCheck {
+            name: name.to_string(),
+            min,
+            max,
+            when: None,
+        }
+    }
+
+    #[test]
+    fn test_limit_thresholds() {
+        let nofile = &LIMITS[0];
+        let result = LimitValidator::new(check("nofile", Some(65536), None)).check_limit(nofile, 1024, 1_048_576);
+        assert!(matches!(result.status, ValidationStatus::Error));
+        assert_eq!(result.message, "Open files limit (nofile) is 1024, need at least 65536");
+        assert_eq!(
+            result.suggestion.as_deref(),
+            Some("Run `ulimit -n 65536` or add it to your shell profile")
+        );
+
+        let result = LimitValidator::new(check("nofile", Some(65536), None)).check_limit(nofile, 1024, 4096);
+        assert!(result.suggestion.unwrap().starts_with("The hard limit is 4096"));
+
+        let memlock = &LIMITS[2];
+        let result = LimitValidator::new(check("memlock", Some(u64::MAX), None)).check_limit(memlock, u64::MAX, u64::MAX);
+        assert_eq!(result.message, "Locked memory limit (memlock) is unlimited");
+
+        let stack = &LIMITS[3];
+        let result = LimitValidator::new(check("stack", None, Some(64 << 20))).check_limit(stack, u64::MAX, u64::MAX);
+        assert!(matches!(result.status, ValidationStatus::Error));
+        assert_eq!(result.suggestion.as_deref(), Some("Run `ulimit -s 65536` or add it to your shell profile"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_current_limits() {
+        let results = LimitValidator::new(check("nofile", Some(1), None)).validate().unwrap();
+        assert!(matches!(results[0].status, ValidationStatus::Success));
+
+        let results = LimitValidator::new(check("files", None, None)).validate().unwrap();
+        assert_eq!(results[0].message, "Unknown limit 'files' in config");
+    }
+}
@@ -1,12 +1,31 @@
-use crate::config::Config;
+use crate::config::{Condition, Config};
 use anyhow::Result;
 use serde::Serialize;
 
 pub mod tool;
+pub mod command;
+pub mod system;
+pub mod os;
+pub mod git;
+pub mod docker;
+pub mod database;
+pub mod library;
+pub mod sysctl;
+pub mod limits;
+pub mod process;
+pub mod service;
+pub mod python;
+pub mod node;
+pub mod ruby;
+pub mod go;
+pub mod cargo;
 pub mod env;
 pub mod port;
 pub mod file;
 pub mod network;
+pub mod proxy;
+pub mod certificate;
+pub mod retry;
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "lowercase")]
@@ -14,6 +33,7 @@ pub enum ValidationStatus {
     Success,
     Warning,
     Error,
+    Skipped,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -47,44 +67,274 @@ impl ValidationResult {
             suggestion,
         }
     }
+
+    pub fn skipped(message: impl Into<String>) -> Self {
+        Self {
+            status: ValidationStatus::Skipped,
+            message: message.into(),
+            suggestion: None,
+        }
+    }
 }
 
 pub trait Validator {
     fn validate(&self) -> Result<Vec<ValidationResult>>;
 }
 
+/// Options that change how checks run, independent of the config file.
+#[derive(Debug, Default, Clone)]
+pub struct RunOptions {
+    /// Skip network checks, and checks that connect to other machines, instead of failing them
+    pub offline: bool,
+}
+
+/// Runs every check in `config` with the default [`RunOptions`].
+// Library API; the binary always passes its command-line options
+#[allow(dead_code)]
 pub fn run_all_validations(config: &Config) -> Result<Vec<ValidationResult>> {
+    run_all_validations_with(config, &RunOptions::default())
+}
+
+pub fn run_all_validations_with(config: &Config, options: &RunOptions) -> Result<Vec<ValidationResult>> {
     let mut results = Vec::new();
+    let platform = os::Platform::detect();
+
+    // Validate the operating system
+    if let Some(os_check) = &config.os {
+        if let Some(skipped) = unmet_condition(&os_check.when, &platform, "OS check") {
+            results.push(skipped);
+        } else {
+            let validator = os::OsValidator::new(os_check.clone());
+            results.extend(validator.validate()?);
+        }
+    }
 
     // Validate tools
     for tool_check in &config.tools {
+        if let Some(skipped) = unmet_condition(&tool_check.when, &platform, &tool_check.name) {
+            results.push(skipped);
+            continue;
+        }
         let validator = tool::ToolValidator::new(tool_check.clone());
         results.extend(validator.validate()?);
     }
 
     // Validate environment variables
     for env_check in &config.env_vars {
+        if let Some(skipped) = unmet_condition(&env_check.when, &platform, &env_check.name) {
+            results.push(skipped);
+            continue;
+        }
         let validator = env::EnvValidator::new(env_check.clone());
         results.extend(validator.validate()?);
     }
 
     // Validate ports
-    for &port in &config.ports {
-        let validator = port::PortValidator::new(port);
+    for port_check in &config.ports {
+        let label = format!("port {}", port_check.port);
+        if let Some(skipped) = unmet_condition(&port_check.when, &platform, &label) {
+            results.push(skipped);
+            continue;
+        }
+        let validator = port::PortValidator::new(port_check.clone());
+        if let Some(target) = validator.remote_target().filter(|_| options.offline) {
+            results.push(ValidationResult::skipped(format!("Skipped {} (offline)", target)));
+            continue;
+        }
         results.extend(validator.validate()?);
     }
 
     // Validate files
     for file_check in &config.files {
+        if let Some(skipped) = unmet_condition(&file_check.when, &platform, &file_check.path) {
+            results.push(skipped);
+            continue;
+        }
         let validator = file::FileValidator::new(file_check.clone());
         results.extend(validator.validate()?);
     }
 
     // Validate network
     for network_check in &config.network {
+        let target = network_check.dns.as_deref().unwrap_or(&network_check.url);
+        if let Some(skipped) = unmet_condition(&network_check.when, &platform, target) {
+            results.push(skipped);
+            continue;
+        }
+        if options.offline {
+            results.push(ValidationResult::skipped(format!("Skipped {} (offline)", target)));
+            continue;
+        }
         let validator = network::NetworkValidator::new(network_check.clone());
         results.extend(validator.validate()?);
     }
 
+    // Validate commands
+    for command_check in &config.commands {
+        let label = format!("`{}`", command_check.run);
+        if let Some(skipped) = unmet_condition(&command_check.when, &platform, &label) {
+            results.push(skipped);
+            continue;
+        }
+        let validator = command::CommandValidator::new(command_check.clone());
+        results.extend(validator.validate()?);
+    }
+
+    // Validate the git repository
+    if let Some(git_check) = &config.git {
+        if let Some(skipped) = unmet_condition(&git_check.when, &platform, "git check") {
+            results.push(skipped);
+        } else {
+            let validator = git::GitValidator::new(git_check.clone());
+            results.extend(validator.validate()?);
+        }
+    }
+
+    // Validate the Docker daemon
+    if let Some(docker_check) = &config.docker {
+        if let Some(skipped) = unmet_condition(&docker_check.when, &platform, "Docker check") {
+            results.push(skipped);
+        } else {
+            let validator = docker::DockerValidator::new(docker_check.clone());
+            match validator.remote_target().filter(|_| options.offline) {
+                Some(target) => results.push(ValidationResult::skipped(format!("Skipped {} (offline)", target))),
+                None => results.extend(validator.validate()?),
+            }
+        }
+    }
+
+    // Validate databases
+    for database_check in &config.databases {
+        if let Some(skipped) = unmet_condition(&database_check.when, &platform, "database check") {
+            results.push(skipped);
+            continue;
+        }
+        let validator = database::DatabaseValidator::new(database_check.clone());
+        if let Some(target) = validator.remote_target().filter(|_| options.offline) {
+            results.push(ValidationResult::skipped(format!("Skipped {} (offline)", target)));
+            continue;
+        }
+        results.extend(validator.validate()?);
+    }
+
+    // Validate native libraries
+    for library_check in &config.libraries {
+        if let Some(skipped) = unmet_condition(&library_check.when, &platform, &library_check.name) {
+            results.push(skipped);
+            continue;
+        }
+        let validator = library::LibraryValidator::new(library_check.clone());
+        results.extend(validator.validate()?);
+    }
+
+    // Validate kernel tunables
+    for sysctl_check in &config.sysctl {
+        if let Some(skipped) = unmet_condition(&sysctl_check.when, &platform, &sysctl_check.name) {
+            results.push(skipped);
+            continue;
+        }
+        let validator = sysctl::SysctlValidator::new(sysctl_check.clone());
+        results.extend(validator.validate()?);
+    }
+
+    // Validate resource limits
+    for limit_check in &config.limits {
+        let label = format!("{} limit", limit_check.name);
+        if let Some(skipped) = unmet_condition(&limit_check.when, &platform, &label) {
+            results.push(skipped);
+            continue;
+        }
+        let validator = limits::LimitValidator::new(limit_check.clone());
+        results.extend(validator.validate()?);
+    }
+
+    // Validate running processes
+    for process_check in &config.processes {
+        let label = process_check.name.as_deref().or(process_check.matches.as_deref()).unwrap_or("process");
+        if let Some(skipped) = unmet_condition(&process_check.when, &platform, label) {
+            results.push(skipped);
+            continue;
+        }
+        let validator = process::ProcessValidator::new(process_check.clone());
+        results.extend(validator.validate()?);
+    }
+
+    // Validate systemd services
+    for service_check in &config.services {
+        if let Some(skipped) = unmet_condition(&service_check.when, &platform, &service_check.name) {
+            results.push(skipped);
+            continue;
+        }
+        let validator = service::ServiceValidator::new(service_check.clone());
+        results.extend(validator.validate()?);
+    }
+
+    // Validate the Python virtualenv
+    if let Some(python_check) = &config.python {
+        if let Some(skipped) = unmet_condition(&python_check.when, &platform, "Python check") {
+            results.push(skipped);
+        } else {
+            let validator = python::PythonValidator::new(python_check.clone());
+            results.extend(validator.validate()?);
+        }
+    }
+
+    // Validate the Node.js project
+    if let Some(node_check) = &config.node {
+        if let Some(skipped) = unmet_condition(&node_check.when, &platform, "Node.js check") {
+            results.push(skipped);
+        } else {
+            let validator = node::NodeValidator::new(node_check.clone());
+            results.extend(validator.validate()?);
+        }
+    }
+
+    // Validate the Ruby project
+    if let Some(ruby_check) = &config.ruby {
+        if let Some(skipped) = unmet_condition(&ruby_check.when, &platform, "Ruby check") {
+            results.push(skipped);
+        } else {
+            let validator = ruby::RubyValidator::new(ruby_check.clone());
+            results.extend(validator.validate()?);
+        }
+    }
+
+    // Validate the Go module
+    if let Some(go_check) = &config.go {
+        if let Some(skipped) = unmet_condition(&go_check.when, &platform, "Go check") {
+            results.push(skipped);
+        } else {
+            let validator = go::GoValidator::new(go_check.clone());
+            results.extend(validator.validate()?);
+        }
+    }
+
+    // Validate the Rust package
+    if let Some(cargo_check) = &config.cargo {
+        if let Some(skipped) = unmet_condition(&cargo_check.when, &platform, "Cargo check") {
+            results.push(skipped);
+        } else {
+            let validator = cargo::CargoValidator::new(cargo_check.clone());
+            results.extend(validator.validate()?);
+        }
+    }
+
+    // Validate system resources
+    if let Some(system_check) = &config.system {
+        if let Some(skipped) = unmet_condition(&system_check.when, &platform, "system resource check") {
+            results.push(skipped);
+        } else {
+            let validator = system::SystemValidator::new(system_check.clone());
+            results.extend(validator.validate()?);
+        }
+    }
+
     Ok(results)
 }
+
+/// Returns a skipped result if the check's `when` condition doesn't match this platform.
+fn unmet_condition(when: &Option<Condition>, platform: &os::Platform, label: &str) -> Option<ValidationResult> {
+    let condition = when.as_ref()?;
+    (!platform.matches(condition))
+        .then(|| ValidationResult::skipped(format!("Skipped {} (only on {})", label, condition)))
+}
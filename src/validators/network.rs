@@ -1,56 +1,782 @@
-use crate::config::NetworkCheck;
+use crate::config::{NetworkCheck, StatusPattern};
+use crate::expand::{expand_path, expand_vars, ExpandError};
+use crate::validators::certificate::inspect_certificate;
+use crate::validators::file::{display_value, values_equal};
+use crate::validators::port::connect_any;
+use crate::validators::proxy::ProxySettings;
+use crate::validators::retry::with_retries;
 use crate::validators::{ValidationResult, Validator};
 use anyhow::Result;
-use reqwest::blocking::Client;
-use std::time::Duration;
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::{HeaderMap, CONTENT_TYPE, LOCATION};
+use reqwest::redirect::Policy;
+use reqwest::Proxy;
+use reqwest::tls::{Certificate, Identity, TlsInfo};
+use reqwest::{Method, Url};
+use serde_json::Value;
+use std::fs;
+use std::net::{IpAddr, ToSocketAddrs};
+use std::time::{Duration, SystemTime};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+const DEFAULT_CERT_WARN_DAYS: u32 = 14;
 
 pub struct NetworkValidator {
     check: NetworkCheck,
-    client: Client,
+    client: std::result::Result<Client, String>,
 }
 
 impl NetworkValidator {
     pub fn new(check: NetworkCheck) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(5))
-            .build()
-            .unwrap_or_else(|_| Client::new());
-            
+        let client = Self::build_client(&check, false);
         Self { check, client }
     }
-}
 
-impl Validator for NetworkValidator {
-    fn validate(&self) -> Result<Vec<ValidationResult>> {
+    /// Builds the HTTP client, loading the CA bundle and client identity from the config.
+    /// `inspect_only` skips certificate verification so a broken certificate can be examined.
+    fn build_client(check: &NetworkCheck, inspect_only: bool) -> std::result::Result<Client, String> {
+        let follow_redirects = check
+            .follow_redirects
+            .unwrap_or(check.expect_redirect.is_none());
+        let redirect_policy = if follow_redirects {
+            Policy::default()
+        } else {
+            Policy::none()
+        };
+        let mut builder = Client::builder()
+            .timeout(check.retry.timeout.unwrap_or(DEFAULT_TIMEOUT))
+            .redirect(redirect_policy)
+            .tls_info(true)
+            .danger_accept_invalid_certs(inspect_only);
+
+        if let Some(ca_bundle) = &check.ca_bundle {
+            let pem = read_pem(ca_bundle)?;
+            let certs = Certificate::from_pem_bundle(&pem)
+                .map_err(|e| format!("Invalid CA bundle {}: {}", ca_bundle, e))?;
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+
+        if let Some(client_cert) = &check.client_cert {
+            let mut pem = read_pem(client_cert)?;
+            if let Some(client_key) = &check.client_key {
+                pem.push(b'\n');
+                pem.extend(read_pem(client_key)?);
+            }
+            let identity = Identity::from_pem(&pem)
+                .map_err(|e| format!("Invalid client certificate {}: {}", client_cert, e))?;
+            builder = builder.identity(identity);
+        }
+
+        let proxy = ProxySettings::from_check(check)?;
+        builder = builder
+            .no_proxy()
+            .proxy(Proxy::custom(move |url| proxy.proxy_for(url)));
+
+        builder.build().map_err(|e| format!("Failed to build HTTP client: {}", e))
+    }
+
+    /// Reports on the certificate the server presents, if the URL is https.
+    fn check_certificate(&self, url: &str, response: &Response, results: &mut Vec<ValidationResult>) {
+        let host = match Url::parse(url) {
+            Ok(parsed) if parsed.scheme() == "https" => parsed.host_str().unwrap_or_default().to_string(),
+            _ => return,
+        };
+        if let Some(der) = response
+            .extensions()
+            .get::<TlsInfo>()
+            .and_then(|info| info.peer_certificate())
+        {
+            let warn_days = self.check.cert_warn_days.unwrap_or(DEFAULT_CERT_WARN_DAYS);
+            results.extend(inspect_certificate(der, &host, warn_days, SystemTime::now()));
+        }
+    }
+
+    /// After a failed https request, connects again without verification to
+    /// explain certificate problems such as expiry or a hostname mismatch.
+    fn diagnose_certificate(&self, url: &str, results: &mut Vec<ValidationResult>) {
+        if !url.starts_with("https://") {
+            return;
+        }
+        let response = Self::build_client(&self.check, true)
+            .ok()
+            .and_then(|client| client.head(url).send().ok());
+        if let Some(response) = response {
+            self.check_certificate(url, &response, results);
+        }
+    }
+
+    /// Builds the request from the config, expanding environment variables in
+    /// header values and string body fields.
+    fn build_request(&self, url: &str) -> std::result::Result<RequestBuilder, String> {
+        let method_name = self.check.method.as_deref().unwrap_or("GET").to_uppercase();
+        let method = Method::from_bytes(method_name.as_bytes())
+            .map_err(|_| format!("Invalid HTTP method '{}'", method_name))?;
+        let client = self.client.as_ref().map_err(|e| e.to_string())?;
+        let mut request = client.request(method, url);
+
+        for (name, value) in &self.check.headers {
+            let value = expand_vars(value).map_err(|e| format!("Invalid header {}: {}", name, e))?;
+            request = request.header(name, value);
+        }
+
+        match &self.check.body {
+            None => {}
+            Some(Value::String(body)) => {
+                request = request.body(expand_vars(body).map_err(|e| format!("Invalid body: {}", e))?);
+            }
+            Some(body) => {
+                let body = expand_json(body).map_err(|e| format!("Invalid body: {}", e))?;
+                let has_content_type = self
+                    .check
+                    .headers
+                    .keys()
+                    .any(|name| name.eq_ignore_ascii_case(CONTENT_TYPE.as_str()));
+                if !has_content_type {
+                    request = request.header(CONTENT_TYPE, "application/json");
+                }
+                request = request.body(body.to_string());
+            }
+        }
+
+        Ok(request)
+    }
+
+    fn accepted_statuses(&self) -> Vec<StatusPattern> {
+        if !self.check.status.is_empty() {
+            self.check.status.clone()
+        } else if let Some(code) = self.check.status_code {
+            vec![StatusPattern::Code(code)]
+        } else if self.check.expect_redirect.is_some() {
+            vec![StatusPattern::Range(300, 399)]
+        } else {
+            vec![StatusPattern::Code(200)]
+        }
+    }
+
+    fn check_redirect(&self, url: &str, headers: &HeaderMap, results: &mut Vec<ValidationResult>) {
+        let expected = match &self.check.expect_redirect {
+            Some(expected) => match expand_vars(expected) {
+                Ok(expected) => expected,
+                Err(e) => {
+                    results.push(ValidationResult::error(
+                        format!("Invalid expect_redirect '{}' in config: {}", expected, e),
+                        Some(e.suggestion()),
+                    ));
+                    return;
+                }
+            },
+            None => return,
+        };
+
+        let location = match headers.get(LOCATION).and_then(|value| value.to_str().ok()) {
+            Some(location) => location,
+            None => {
+                results.push(ValidationResult::error(
+                    format!("{} did not redirect", url),
+                    Some(format!("Expected a redirect to {}", expected)),
+                ));
+                return;
+            }
+        };
+
+        // Relative locations and expectations are resolved against the request URL
+        let resolve = |target: &str| Url::parse(url).and_then(|base| base.join(target)).ok();
+        if location == expected || resolve(location).is_some_and(|l| Some(l) == resolve(&expected)) {
+            results.push(ValidationResult::success(format!(
+                "{} redirects to {}",
+                url, location
+            )));
+        } else {
+            results.push(ValidationResult::error(
+                format!("{} redirects to {}, expected {}", url, location, expected),
+                None,
+            ));
+        }
+    }
+
+    /// Sends the request once and checks the response against every expectation.
+    fn probe(&self, url: &str, request: RequestBuilder) -> Vec<ValidationResult> {
         let mut results = Vec::new();
-        
-        let url = &self.check.url;
-        let expected_status = self.check.status_code.unwrap_or(200);
-        
-        match self.client.get(url).send() {
+        let accepted_statuses = self.accepted_statuses();
+
+        match request.send() {
             Ok(response) => {
                 let status = response.status().as_u16();
-                
-                if status == expected_status {
+
+                if accepted_statuses.iter().any(|pattern| pattern.matches(status)) {
                     results.push(ValidationResult::success(format!(
-                        "Successfully connected to {} (Status: {})", 
+                        "Successfully connected to {} (Status: {})",
                         url, status
                     )));
                 } else {
+                    let expected = accepted_statuses
+                        .iter()
+                        .map(|pattern| pattern.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" or ");
                     results.push(ValidationResult::error(
                         format!("Connected to {} but got status {}", url, status),
-                        Some(format!("Expected status code {}", expected_status)),
+                        Some(format!("Expected status code {}", expected)),
                     ));
                 }
+
+                self.check_certificate(url, &response, &mut results);
+                self.check_redirect(url, response.headers(), &mut results);
+                self.check_headers(url, response.headers(), &mut results);
+                if self.has_body_assertions() {
+                    self.check_body(url, response, &mut results);
+                }
             }
             Err(e) => {
+                let via_proxy = Url::parse(url).ok().and_then(|parsed| {
+                    ProxySettings::from_check(&self.check)
+                        .ok()?
+                        .proxy_for(&parsed)
+                });
+                let suggestion = match via_proxy {
+                    Some(proxy) => format!("Error: {} (via proxy {})", e, proxy),
+                    None => format!("Error: {}", e),
+                };
                 results.push(ValidationResult::error(
                     format!("Failed to connect to {}", url),
+                    Some(suggestion),
+                ));
+                self.diagnose_certificate(url, &mut results);
+            }
+        }
+
+        results
+    }
+
+    fn has_body_assertions(&self) -> bool {
+        !self.check.expect_body_contains.is_empty()
+            || self.check.expect_body_matches.is_some()
+            || !self.check.expect_json.is_empty()
+    }
+
+    fn check_headers(&self, url: &str, headers: &HeaderMap, results: &mut Vec<ValidationResult>) {
+        for (name, expected) in &self.check.expect_headers {
+            match headers.get(name).map(|value| value.to_str().unwrap_or_default()) {
+                Some(actual) if actual == expected => results.push(ValidationResult::success(
+                    format!("{} returned header {}: {}", url, name, actual),
+                )),
+                Some(actual) => results.push(ValidationResult::error(
+                    format!("{} returned header {}: {}, expected {}", url, name, actual, expected),
+                    None,
+                )),
+                None => results.push(ValidationResult::error(
+                    format!("{} did not return header {}", url, name),
+                    Some(format!("Expected {}: {}", name, expected)),
+                )),
+            }
+        }
+    }
+
+    fn check_body(&self, url: &str, response: Response, results: &mut Vec<ValidationResult>) {
+        let body = match response.text() {
+            Ok(body) => body,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Failed to read response body from {}", url),
                     Some(format!("Error: {}", e)),
                 ));
+                return;
+            }
+        };
+
+        for needle in &self.check.expect_body_contains {
+            if body.contains(needle.as_str()) {
+                results.push(ValidationResult::success(format!(
+                    "{} response contains '{}'",
+                    url, needle
+                )));
+            } else {
+                results.push(ValidationResult::error(
+                    format!("{} response does not contain '{}'", url, needle),
+                    Some(format!("Response body: {}", truncate(&body))),
+                ));
+            }
+        }
+
+        if let Some(pattern) = &self.check.expect_body_matches {
+            let is_match = match regex::Regex::new(pattern) {
+                Ok(re) => re.is_match(&body),
+                Err(_) => body.contains(pattern.as_str()), // Fallback to substring if regex is invalid
+            };
+            if is_match {
+                results.push(ValidationResult::success(format!(
+                    "{} response matches pattern {}",
+                    url, pattern
+                )));
+            } else {
+                results.push(ValidationResult::error(
+                    format!("{} response does not match pattern {}", url, pattern),
+                    Some(format!("Response body: {}", truncate(&body))),
+                ));
+            }
+        }
+
+        if self.check.expect_json.is_empty() {
+            return;
+        }
+
+        let document: Value = match serde_json::from_str(&body) {
+            Ok(document) => document,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("{} did not return valid JSON", url),
+                    Some(format!("Error: {}", e)),
+                ));
+                return;
+            }
+        };
+
+        for (pointer, expected) in &self.check.expect_json {
+            match document.pointer(pointer) {
+                Some(actual) if values_equal(actual, expected) => {
+                    results.push(ValidationResult::success(format!(
+                        "{} response {} = {}",
+                        url,
+                        pointer,
+                        display_value(actual)
+                    )));
+                }
+                Some(actual) => results.push(ValidationResult::error(
+                    format!(
+                        "{} response {} is {}, expected {}",
+                        url,
+                        pointer,
+                        display_value(actual),
+                        display_value(expected)
+                    ),
+                    None,
+                )),
+                None => results.push(ValidationResult::error(
+                    format!("{} response has no value at {}", url, pointer),
+                    Some(format!("Response body: {}", truncate(&body))),
+                )),
+            }
+        }
+    }
+}
+
+fn read_pem(path: &str) -> std::result::Result<Vec<u8>, String> {
+    let expanded = expand_path(path).map_err(|e| format!("Invalid path '{}': {}", path, e))?;
+    fs::read(&expanded).map_err(|e| format!("Failed to read {}: {}", expanded, e))
+}
+
+/// Expands environment variables in every string inside a JSON value.
+fn expand_json(value: &Value) -> std::result::Result<Value, ExpandError> {
+    Ok(match value {
+        Value::String(s) => Value::String(expand_vars(s)?),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(expand_json)
+                .collect::<std::result::Result<_, ExpandError>>()?,
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| Ok((key.clone(), expand_json(value)?)))
+                .collect::<std::result::Result<_, ExpandError>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+/// Shortens response bodies quoted in suggestions.
+fn truncate(body: &str) -> String {
+    const MAX_LEN: usize = 200;
+    let body = body.trim();
+    match body.char_indices().nth(MAX_LEN) {
+        Some((end, _)) => format!("{}...", &body[..end]),
+        None => body.to_string(),
+    }
+}
+
+impl NetworkValidator {
+    /// Opens a TCP connection to a `tcp://host:port` URL.
+    fn probe_tcp(&self, url: &str) -> Vec<ValidationResult> {
+        let target = match Url::parse(url) {
+            Ok(parsed) => parsed
+                .host_str()
+                .zip(parsed.port())
+                .map(|(host, port)| (host.trim_start_matches('[').trim_end_matches(']').to_string(), port)),
+            Err(_) => None,
+        };
+        let Some((host, port)) = target else {
+            return vec![ValidationResult::error(
+                format!("Invalid URL '{}' in config", url),
+                Some("Use the form tcp://host:port".to_string()),
+            )];
+        };
+
+        let timeout = self.check.retry.timeout.unwrap_or(DEFAULT_TIMEOUT);
+        let connected = (host.as_str(), port)
+            .to_socket_addrs()
+            .and_then(|addrs| connect_any(addrs, timeout));
+        match connected {
+            Ok(_) => vec![ValidationResult::success(format!("Connected to {}", url))],
+            Err(e) => vec![ValidationResult::error(
+                format!("Failed to connect to {}", url),
+                Some(format!("Check that the service is running and reachable (Error: {})", e)),
+            )],
+        }
+    }
+
+    /// Resolves a hostname and compares the addresses against `resolves_to`.
+    fn probe_dns(&self, host: &str) -> Vec<ValidationResult> {
+        let mut resolved: Vec<IpAddr> = match (host, 0).to_socket_addrs() {
+            Ok(addrs) => addrs.map(|addr| addr.ip()).collect(),
+            Err(e) => {
+                return vec![ValidationResult::error(
+                    format!("{} does not resolve", host),
+                    Some(format!(
+                        "Add {} to /etc/hosts or check your DNS settings (Error: {})",
+                        host, e
+                    )),
+                )]
+            }
+        };
+        resolved.sort();
+        resolved.dedup();
+        let addresses = resolved
+            .iter()
+            .map(|ip| ip.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let missing: Vec<String> = self
+            .check
+            .resolves_to
+            .iter()
+            .filter(|expected| !resolved.contains(expected))
+            .map(|expected| expected.to_string())
+            .collect();
+        if !missing.is_empty() {
+            return vec![ValidationResult::error(
+                format!("{} resolves to {}, expected {}", host, addresses, missing.join(", ")),
+                Some(format!("Update the DNS record or /etc/hosts entry for {}", host)),
+            )];
+        }
+
+        vec![ValidationResult::success(format!("{} resolves to {}", host, addresses))]
+    }
+}
+
+impl Validator for NetworkValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        let mut results = Vec::new();
+
+        if let Some(host) = &self.check.dns {
+            let host = match expand_vars(host) {
+                Ok(host) => host,
+                Err(e) => {
+                    results.push(ValidationResult::error(
+                        format!("Invalid dns hostname '{}' in config: {}", host, e),
+                        Some(e.suggestion()),
+                    ));
+                    return Ok(results);
+                }
+            };
+            return Ok(with_retries(&self.check.retry, || self.probe_dns(&host)));
+        }
+        if self.check.url.is_empty() {
+            results.push(ValidationResult::error(
+                "Network check has neither a url nor a dns hostname".to_string(),
+                Some("Set url (http://, https:// or tcp://) or dns in your config".to_string()),
+            ));
+            return Ok(results);
+        }
+
+        let url = &match expand_vars(&self.check.url) {
+            Ok(url) => url,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Invalid URL '{}' in config: {}", self.check.url, e),
+                    Some(e.suggestion()),
+                ));
+                return Ok(results);
             }
+        };
+
+        if url.starts_with("tcp://") {
+            return Ok(with_retries(&self.check.retry, || self.probe_tcp(url)));
         }
 
-        Ok(results)
+        let request = match self.build_request(url) {
+            Ok(request) => request,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Invalid request for {} in config", url),
+                    Some(e),
+                ));
+                return Ok(results);
+            }
+        };
+
+        Ok(with_retries(&self.check.retry, || match request.try_clone() {
+            Some(request) => self.probe(url, request),
+            None => vec![ValidationResult::error(
+                format!("Failed to send request to {}", url),
+                Some("The request body cannot be replayed".to_string()),
+            )],
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RetryConfig;
+    use crate::validators::ValidationStatus;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    /// Serves `responses` to consecutive connections on a local port and
+    /// returns the base URL plus a handle yielding the raw requests received.
+    fn stub_server(responses: Vec<String>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                        break;
+                    }
+                    if let Some(len) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = len.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                request.push_str("\r\n");
+                request.push_str(&String::from_utf8_lossy(&body));
+                stream.write_all(response.as_bytes()).unwrap();
+                requests.push(request);
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    fn http_response(status: &str, headers: &[(&str, &str)], body: &str) -> String {
+        let mut response = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n", status, body.len());
+        for (name, value) in headers {
+            response.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        response.push_str("\r\n");
+        response.push_str(body);
+        response
+    }
+
+    #[test]
+    fn test_request_and_response_assertions() {
+        std::env::set_var("ENVCHECK_NETWORK_TEST_TOKEN", "s3cret");
+        let body = r#"{"status": "up", "checks": {"db": {"latency_ms": 3}}}"#;
+        let (url, server) = stub_server(vec![http_response(
+            "200 OK",
+            &[("Content-Type", "application/json"), ("X-Version", "2")],
+            body,
+        )]);
+
+        let check = NetworkCheck {
+            url: format!("{}/health", url),
+            method: Some("post".to_string()),
+            headers: [("Authorization".to_string(), "Bearer ${ENVCHECK_NETWORK_TEST_TOKEN}".to_string())].into(),
+            body: Some(serde_json::json!({"probe": "${ENVCHECK_NETWORK_TEST_TOKEN}"})),
+            expect_body_contains: vec!["\"up\"".to_string(), "down".to_string()],
+            expect_body_matches: Some(r#""latency_ms": \d+"#.to_string()),
+            expect_json: [
+                ("/status".to_string(), serde_json::json!("up")),
+                ("/checks/db/latency_ms".to_string(), serde_json::json!("3")),
+                ("/checks/cache".to_string(), serde_json::json!(true)),
+            ]
+            .into(),
+            expect_headers: [
+                ("content-type".to_string(), "application/json".to_string()),
+                ("x-version".to_string(), "3".to_string()),
+            ]
+            .into(),
+            ..Default::default()
+        };
+        let results = NetworkValidator::new(check).validate().unwrap();
+        let request = server.join().unwrap().remove(0);
+
+        assert!(request.starts_with("POST /health HTTP/1.1"));
+        assert!(request.contains("authorization: Bearer s3cret"));
+        assert!(request.contains("content-type: application/json"));
+        assert!(request.ends_with(r#"{"probe":"s3cret"}"#));
+
+        let errors: Vec<&str> = results
+            .iter()
+            .filter(|r| matches!(r.status, ValidationStatus::Error))
+            .map(|r| r.message.as_str())
+            .collect();
+        assert_eq!(results.len(), 9);
+        assert_eq!(
+            errors,
+            vec![
+                format!("{}/health returned header x-version: 2, expected 3", url),
+                format!("{}/health response does not contain 'down'", url),
+                format!("{}/health response has no value at /checks/cache", url),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_method() {
+        let check = NetworkCheck {
+            url: "http://127.0.0.1:1".to_string(),
+            method: Some("NOT A METHOD".to_string()),
+            ..Default::default()
+        };
+        let results = NetworkValidator::new(check).validate().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].suggestion.as_deref(), Some("Invalid HTTP method 'NOT A METHOD'"));
+    }
+
+    #[test]
+    fn test_status_patterns_and_redirects() {
+        let (url, server) = stub_server(vec![
+            http_response("204 No Content", &[], ""),
+            http_response("302 Found", &[("Location", "/login")], ""),
+            http_response("301 Moved Permanently", &[("Location", "https://example.com/")], ""),
+        ]);
+
+        let validate = |check: NetworkCheck| NetworkValidator::new(check).validate().unwrap();
+
+        let results = validate(NetworkCheck {
+            url: format!("{}/empty", url),
+            status: vec![StatusPattern::Code(200), StatusPattern::Code(204)],
+            ..Default::default()
+        });
+        assert!(matches!(results[0].status, ValidationStatus::Success));
+
+        let results = validate(NetworkCheck {
+            url: format!("{}/account", url),
+            expect_redirect: Some(format!("{}/login", url)),
+            ..Default::default()
+        });
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| matches!(r.status, ValidationStatus::Success)));
+        assert_eq!(results[1].message, format!("{}/account redirects to /login", url));
+
+        let results = validate(NetworkCheck {
+            url: format!("{}/old", url),
+            status: vec![StatusPattern::Range(200, 299)],
+            follow_redirects: Some(false),
+            expect_redirect: Some("/new".to_string()),
+            ..Default::default()
+        });
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+        assert_eq!(results[0].suggestion.as_deref(), Some("Expected status code 2xx"));
+        assert_eq!(results[1].message, format!("{}/old redirects to https://example.com/, expected /new", url));
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_retries_until_healthy() {
+        let (url, server) = stub_server(vec![
+            http_response("503 Service Unavailable", &[], "starting"),
+            http_response("200 OK", &[], "ok"),
+        ]);
+
+        let check = NetworkCheck {
+            url,
+            retry: RetryConfig {
+                retries: 2,
+                retry_interval: Some(Duration::from_millis(1)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let results = NetworkValidator::new(check.clone()).validate().unwrap();
+        server.join().unwrap();
+
+        assert!(matches!(results[0].status, ValidationStatus::Success));
+        assert_eq!(
+            results[0].message,
+            format!("Successfully connected to {} (Status: 200) (after 2 attempts)", check.url)
+        );
+    }
+
+    #[test]
+    fn test_missing_ca_bundle() {
+        let check = NetworkCheck {
+            url: "https://127.0.0.1:1".to_string(),
+            ca_bundle: Some("/nonexistent/ca.pem".to_string()),
+            ..Default::default()
+        };
+        let results = NetworkValidator::new(check).validate().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].message, "Invalid request for https://127.0.0.1:1 in config");
+        assert!(results[0]
+            .suggestion
+            .as_deref()
+            .unwrap()
+            .starts_with("Failed to read /nonexistent/ca.pem"));
+    }
+
+    #[test]
+    fn test_tcp_connect() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("tcp://{}", listener.local_addr().unwrap());
+        let check = NetworkCheck { url: url.clone(), ..Default::default() };
+
+        let results = NetworkValidator::new(check.clone()).validate().unwrap();
+        assert!(matches!(results[0].status, ValidationStatus::Success));
+        assert_eq!(results[0].message, format!("Connected to {}", url));
+
+        drop(listener);
+        let results = NetworkValidator::new(check).validate().unwrap();
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+        assert_eq!(results[0].message, format!("Failed to connect to {}", url));
+
+        let check = NetworkCheck { url: "tcp://localhost".to_string(), ..Default::default() };
+        let results = NetworkValidator::new(check).validate().unwrap();
+        assert_eq!(results[0].message, "Invalid URL 'tcp://localhost' in config");
+    }
+
+    #[test]
+    fn test_dns_resolution() {
+        let check = NetworkCheck {
+            dns: Some("localhost".to_string()),
+            resolves_to: vec![IpAddr::from([127, 0, 0, 1])],
+            ..Default::default()
+        };
+        let results = NetworkValidator::new(check).validate().unwrap();
+        assert!(matches!(results[0].status, ValidationStatus::Success));
+        assert!(results[0].message.starts_with("localhost resolves to "));
+
+        let check = NetworkCheck {
+            dns: Some("localhost".to_string()),
+            resolves_to: vec![IpAddr::from([10, 9, 9, 9])],
+            ..Default::default()
+        };
+        let results = NetworkValidator::new(check).validate().unwrap();
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+        assert!(results[0].message.ends_with(", expected 10.9.9.9"));
+
+        let check = NetworkCheck { dns: Some("missing.invalid".to_string()), ..Default::default() };
+        let results = NetworkValidator::new(check).validate().unwrap();
+        assert_eq!(results[0].message, "missing.invalid does not resolve");
+
+        std::env::set_var("ENVCHECK_DNS_TEST_HOST", "localhost");
+        let check = NetworkCheck { dns: Some("${ENVCHECK_DNS_TEST_HOST}".to_string()), ..Default::default() };
+        let results = NetworkValidator::new(check).validate().unwrap();
+        assert!(results[0].message.starts_with("localhost resolves to "));
     }
 }
@@ -0,0 +1,581 @@
+use crate::config::NodeCheck;
+use crate::expand::expand_path;
+use crate::validators::{ValidationResult, Validator};
+use anyhow::Result;
+use regex::Regex;
+use semver::{Version, VersionReq};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// How many out-of-date packages are listed before "and N more".
+const MAX_LISTED: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PackageManager {
+    Npm,
+    Pnpm,
+    Yarn,
+}
+
+impl PackageManager {
+    const ALL: [PackageManager; 3] = [PackageManager::Npm, PackageManager::Pnpm, PackageManager::Yarn];
+
+    fn lockfile(self) -> &'static str {
+        match self {
+            PackageManager::Npm => "package-lock.json",
+            PackageManager::Pnpm => "pnpm-lock.yaml",
+            PackageManager::Yarn => "yarn.lock",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|manager| manager.to_string() == name)
+    }
+}
+
+impl fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PackageManager::Npm => "npm",
+            PackageManager::Pnpm => "pnpm",
+            PackageManager::Yarn => "yarn",
+        })
+    }
+}
+
+/// A package the lockfile expects at `path` (e.g. `node_modules/react`).
+#[derive(Debug, Clone, PartialEq)]
+struct Locked {
+    path: String,
+    version: String,
+}
+
+impl Locked {
+    fn name(&self) -> &str {
+        self.path.rsplit_once("node_modules/").map_or(&self.path, |(_, name)| name)
+    }
+}
+
+pub struct NodeValidator {
+    check: NodeCheck,
+}
+
+impl NodeValidator {
+    pub fn new(check: NodeCheck) -> Self {
+        Self { check }
+    }
+
+    fn check_dependencies(&self, dir: &Path, manifest: &Value, results: &mut Vec<ValidationResult>) {
+        let Some(manager) = PackageManager::ALL
+            .into_iter()
+            .find(|manager| dir.join(manager.lockfile()).is_file())
+        else {
+            results.push(ValidationResult::warning(
+                format!("No lockfile found in {}", dir.display()),
+                Some("Run `npm install` (or pnpm/yarn) and commit the lockfile".to_string()),
+            ));
+            return;
+        };
+        let lockfile = manager.lockfile();
+        let install = format!("Run `{} install`", manager);
+
+        // Yarn Plug'n'Play installs into .pnp.cjs and a zip cache instead of node_modules
+        if manager == PackageManager::Yarn && dir.join(".pnp.cjs").is_file() {
+            results.push(ValidationResult::skipped(format!(
+                "Skipped node_modules check in {} (Yarn Plug'n'Play)",
+                dir.display()
+            )));
+            return;
+        }
+
+        if !dir.join("node_modules").is_dir() {
+            results.push(ValidationResult::error(
+                format!("node_modules is missing in {}", dir.display()),
+                Some(install),
+            ));
+            return;
+        }
+
+        let locked = fs::read_to_string(dir.join(lockfile))
+            .map_err(|e| e.to_string())
+            .and_then(|contents| match manager {
+                PackageManager::Npm => parse_npm_lock(&contents),
+                PackageManager::Pnpm => parse_pnpm_lock(&contents),
+                PackageManager::Yarn => Ok(parse_yarn_lock(&contents, manifest)),
+            });
+        let locked = match locked {
+            Ok(locked) => locked,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Cannot parse {}: {}", lockfile, e),
+                    Some(format!("Regenerate it with `{} install`", manager)),
+                ));
+                return;
+            }
+        };
+
+        // npm records what it installed in node_modules/.package-lock.json
+        let hidden_lock = fs::read_to_string(dir.join("node_modules/.package-lock.json"))
+            .ok()
+            .and_then(|contents| parse_npm_lock(&contents).ok())
+            .filter(|_| manager == PackageManager::Npm)
+            .map(|installed| {
+                installed
+                    .into_iter()
+                    .map(|package| (package.path, package.version))
+                    .collect::<HashMap<_, _>>()
+            });
+
+        let mut stale = Vec::new();
+        for package in &locked {
+            let installed = match &hidden_lock {
+                Some(installed) => installed.get(&package.path).cloned(),
+                None => installed_version(&dir.join(&package.path)),
+            };
+            match installed {
+                None => stale.push(format!("{} missing", package.name())),
+                Some(version) if version != package.version => stale.push(format!(
+                    "{} {} installed, {} locked",
+                    package.name(),
+                    version,
+                    package.version
+                )),
+                Some(_) => {}
+            }
+        }
+
+        if stale.is_empty() {
+            results.push(ValidationResult::success(format!(
+                "node_modules matches {} ({} packages)",
+                lockfile,
+                locked.len()
+            )));
+        } else {
+            let more = match stale.len().saturating_sub(MAX_LISTED) {
+                0 => String::new(),
+                n => format!(" (and {} more)", n),
+            };
+            stale.truncate(MAX_LISTED);
+            results.push(ValidationResult::error(
+                format!("node_modules is out of date with {}: {}{}", lockfile, stale.join("; "), more),
+                Some(install),
+            ));
+        }
+    }
+
+    fn check_engines(&self, dir: &Path, manifest: &Value, results: &mut Vec<ValidationResult>) {
+        let Some(range) = manifest.pointer("/engines/node").and_then(Value::as_str) else {
+            return;
+        };
+        let Some(version) = tool_version("node", dir) else {
+            results.push(ValidationResult::error(
+                "node not found".to_string(),
+                Some(format!("Install Node.js {}", range)),
+            ));
+            return;
+        };
+
+        match npm_range_matches(&version, range) {
+            Some(true) => results.push(ValidationResult::success(format!(
+                "node {} satisfies engines.node {}",
+                version, range
+            ))),
+            Some(false) => results.push(ValidationResult::error(
+                format!("node {} does not satisfy engines.node {}", version, range),
+                Some(format!("Switch to node {} (e.g. with nvm, fnm or volta)", range)),
+            )),
+            None => results.push(ValidationResult::warning(
+                format!("Cannot parse engines.node '{}' in package.json", range),
+                Some("Use a semver range like \">=18\"".to_string()),
+            )),
+        }
+    }
+
+    fn check_package_manager(&self, dir: &Path, manifest: &Value, results: &mut Vec<ValidationResult>) {
+        let Some(declared) = manifest.get("packageManager").and_then(Value::as_str) else {
+            return;
+        };
+        // e.g. "pnpm@8.15.4+sha512.abc"
+        let (name, version) = declared.split_once('@').unwrap_or((declared, ""));
+        let version = version.split('+').next().unwrap_or_default();
+
+        if let Some(manager) = PackageManager::from_name(name) {
+            let other = PackageManager::ALL
+                .into_iter()
+                .find(|other| *other != manager && dir.join(other.lockfile()).is_file());
+            if let Some(other) = other {
+                results.push(ValidationResult::warning(
+                    format!("package.json declares {} but the project has a {}", name, other.lockfile()),
+                    Some(format!("Remove {} and run `{} install`", other.lockfile(), manager)),
+                ));
+            }
+        }
+
+        let corepack = "Run `corepack enable` so the declared version is used in this project".to_string();
+        match tool_version(name, dir) {
+            None => results.push(ValidationResult::error(
+                format!("{} not found (package.json declares {}@{})", name, name, version),
+                Some(corepack),
+            )),
+            Some(active) if active.to_string() != version => results.push(ValidationResult::error(
+                format!("{} {} is active, but package.json declares {}@{}", name, active, name, version),
+                Some(corepack),
+            )),
+            Some(active) => results.push(ValidationResult::success(format!(
+                "{} {} matches packageManager",
+                name, active
+            ))),
+        }
+    }
+}
+
+impl Validator for NodeValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        let mut results = Vec::new();
+
+        let dir = match expand_path(self.check.path.as_deref().unwrap_or(".")) {
+            Ok(dir) => PathBuf::from(dir),
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Invalid node path in config: {}", e),
+                    Some(e.suggestion()),
+                ));
+                return Ok(results);
+            }
+        };
+        let manifest = match fs::read_to_string(dir.join("package.json")) {
+            Ok(contents) => match serde_json::from_str::<Value>(&contents) {
+                Ok(manifest) => manifest,
+                Err(e) => {
+                    results.push(ValidationResult::error(
+                        format!("Cannot parse {}: {}", dir.join("package.json").display(), e),
+                        None,
+                    ));
+                    return Ok(results);
+                }
+            },
+            Err(_) => {
+                results.push(ValidationResult::error(
+                    format!("No package.json in {}", dir.display()),
+                    Some("Set node.path to the directory of your Node.js project".to_string()),
+                ));
+                return Ok(results);
+            }
+        };
+
+        if self.check.dependencies {
+            self.check_dependencies(&dir, &manifest, &mut results);
+        }
+        if self.check.engines {
+            self.check_engines(&dir, &manifest, &mut results);
+        }
+        if self.check.package_manager {
+            self.check_package_manager(&dir, &manifest, &mut results);
+        }
+        Ok(results)
+    }
+}
+
+/// Runs `tool --version` in `dir`, so version managers and corepack pick the project's version.
+fn tool_version(tool: &str, dir: &Path) -> Option<Version> {
+    let program = which::which(tool).ok()?;
+    let output = Command::new(program).arg("--version").current_dir(dir).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Version::parse(stdout.trim().trim_start_matches('v')).ok()
+}
+
+fn installed_version(package_dir: &Path) -> Option<String> {
+    let contents = fs::read_to_string(package_dir.join("package.json")).ok()?;
+    let manifest: Value = serde_json::from_str(&contents).ok()?;
+    manifest.get("version")?.as_str().map(str::to_string)
+}
+
+/// Reads the install tree of a package-lock.json (lockfileVersion 2 or 3),
+/// or the top-level dependencies of a version 1 lockfile. Links and optional
+/// (often platform-specific) packages are skipped.
+fn parse_npm_lock(contents: &str) -> std::result::Result<Vec<Locked>, String> {
+    let lock: Value = serde_json::from_str(contents).map_err(|e| e.to_string())?;
+    let skipped = |entry: &Value| {
+        ["link", "optional", "extraneous"]
+            .iter()
+            .any(|key| entry.get(key).and_then(Value::as_bool) == Some(true))
+    };
+
+    let mut locked = Vec::new();
+    if let Some(packages) = lock.get("packages").and_then(Value::as_object) {
+        for (path, entry) in packages {
+            if !path.starts_with("node_modules/") || skipped(entry) {
+                continue;
+            }
+            if let Some(version) = entry.get("version").and_then(Value::as_str) {
+                locked.push(Locked { path: path.clone(), version: version.to_string() });
+            }
+        }
+    } else if let Some(dependencies) = lock.get("dependencies").and_then(Value::as_object) {
+        for (name, entry) in dependencies {
+            if skipped(entry) {
+                continue;
+            }
+            if let Some(version) = entry.get("version").and_then(Value::as_str) {
+                locked.push(Locked { path: format!("node_modules/{}", name), version: version.to_string() });
+            }
+        }
+    }
+    Ok(locked)
+}
+
+/// Reads the direct dependencies of the root project from pnpm-lock.yaml,
+/// in both the `importers` (v6+) and the older top-level layout.
+fn parse_pnpm_lock(contents: &str) -> std::result::Result<Vec<Locked>, String> {
+    let lock: serde_yaml::Value = serde_yaml::from_str(contents).map_err(|e| e.to_string())?;
+    let root = lock.get("importers").and_then(|importers| importers.get(".")).unwrap_or(&lock);
+    // Before v6, peer dependencies were appended as "18.2.0_react@18.2.0"
+    let lockfile_version = match lock.get("lockfileVersion") {
+        Some(serde_yaml::Value::String(version)) => version.parse().ok(),
+        Some(version) => version.as_f64(),
+        None => None,
+    };
+    let underscore_peers = lockfile_version.is_some_and(|version| version < 6.0);
+
+    let mut locked = Vec::new();
+    for section in ["dependencies", "devDependencies"] {
+        let Some(dependencies) = root.get(section).and_then(|d| d.as_mapping()) else {
+            continue;
+        };
+        for (name, entry) in dependencies {
+            let version = entry.get("version").unwrap_or(entry).as_str().unwrap_or_default();
+            // "18.2.0(react@18.2.0)" carries peer dependencies in parentheses
+            let version = version.split('(').next().unwrap_or_default();
+            let version = match underscore_peers {
+                true => version.split('_').next().unwrap_or_default(),
+                false => version,
+            };
+            if version.is_empty() || version.contains(':') {
+                continue;
+            }
+            if let Some(name) = name.as_str() {
+                locked.push(Locked { path: format!("node_modules/{}", name), version: version.to_string() });
+            }
+        }
+    }
+    Ok(locked)
+}
+
+/// Resolves the direct dependencies in package.json through yarn.lock, which
+/// maps descriptors like `lodash@^4.17.0` (v1) or `lodash@npm:^4.17.0` (berry) to versions.
+fn parse_yarn_lock(contents: &str, manifest: &Value) -> Vec<Locked> {
+    let mut resolved: HashMap<String, String> = HashMap::new();
+    let mut descriptors: Vec<String> = Vec::new();
+    for line in contents.lines() {
+        if !line.starts_with(' ') && line.ends_with(':') && !line.starts_with('#') {
+            descriptors = line
+                .trim_end_matches(':')
+                .split(", ")
+                .map(|descriptor| descriptor.trim_matches('"').to_string())
+                .collect();
+        } else if let Some(version) = line.trim().strip_prefix("version") {
+            let version = version.trim_start_matches(':').trim().trim_matches('"');
+            for descriptor in descriptors.drain(..) {
+                resolved.insert(descriptor, version.to_string());
+            }
+        }
+    }
+
+    let mut locked = Vec::new();
+    for section in ["dependencies", "devDependencies"] {
+        let Some(dependencies) = manifest.get(section).and_then(Value::as_object) else {
+            continue;
+        };
+        for (name, spec) in dependencies {
+            let spec = spec.as_str().unwrap_or_default();
+            let version = resolved
+                .get(&format!("{}@{}", name, spec))
+                .or_else(|| resolved.get(&format!("{}@npm:{}", name, spec)));
+            if let Some(version) = version {
+                locked.push(Locked { path: format!("node_modules/{}", name), version: version.clone() });
+            }
+        }
+    }
+    locked
+}
+
+/// Checks a version against an npm range such as `>=18 <21`, `^18 || ^20` or
+/// `1.2 - 2.3`. Returns None if the range can't be parsed.
+fn npm_range_matches(version: &Version, range: &str) -> Option<bool> {
+    let operator_space = Regex::new(r"(>=|<=|>|<|=|~|\^)\s+").expect("valid regex");
+    let mut matched = false;
+    for alternative in range.split("||").map(str::trim) {
+        let requirement = match alternative.split_once(" - ") {
+            Some((low, high)) => format!(">={}, <={}", low.trim(), high.trim()),
+            None if alternative.is_empty() => "*".to_string(),
+            None => operator_space
+                .replace_all(alternative, "$1")
+                .split_whitespace()
+                // A bare version is exact in npm but a caret requirement in Cargo
+                .map(|part| match part.starts_with(|c: char| c.is_ascii_digit()) {
+                    true => format!("={}", part),
+                    false => part.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        matched |= VersionReq::parse(&requirement).ok()?.matches(version);
+    }
+    Some(matched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validators::ValidationStatus;
+
+    #[test]
+    fn test_npm_ranges() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        assert_eq!(npm_range_matches(&v("20.11.0"), ">=18"), Some(true));
+        assert_eq!(npm_range_matches(&v("20.11.0"), ">= 18 < 20"), Some(false));
+        assert_eq!(npm_range_matches(&v("20.11.0"), "^18 || ^20"), Some(true));
+        assert_eq!(npm_range_matches(&v("16.20.2"), "18.x || >=20"), Some(false));
+        assert_eq!(npm_range_matches(&v("18.5.0"), "16 - 18"), Some(true));
+        assert_eq!(npm_range_matches(&v("18.5.0"), "lts"), None);
+        assert_eq!(npm_range_matches(&v("20.12.0"), "20.11.0"), Some(false));
+        assert_eq!(npm_range_matches(&v("20.11.0"), "20.11.0"), Some(true));
+        assert_eq!(npm_range_matches(&v("18.5.0"), "18 || 20.x"), Some(true));
+    }
+
+    #[test]
+    fn test_parse_lockfiles() {
+        let pnpm = r#"
+lockfileVersion: '9.0'
+importers:
+  .:
+    dependencies:
+      react-dom:
+        specifier: ^18.2.0
+        version: 18.2.0(react@18.2.0)
+      shared:
+        specifier: workspace:*
+        version: link:../shared
+    devDependencies:
+      typescript:
+        specifier: ^5.3.0
+        version: 5.3.3
+"#;
+        let locked = parse_pnpm_lock(pnpm).unwrap();
+        assert_eq!(
+            locked,
+            vec![
+                Locked { path: "node_modules/react-dom".to_string(), version: "18.2.0".to_string() },
+                Locked { path: "node_modules/typescript".to_string(), version: "5.3.3".to_string() },
+            ]
+        );
+
+        let pnpm_v5 = r#"
+lockfileVersion: 5.4
+specifiers:
+  react-dom: ^18.2.0
+  typescript: ^5.3.0
+dependencies:
+  react-dom: 18.2.0_react@18.2.0
+devDependencies:
+  typescript: 5.3.3
+"#;
+        assert_eq!(parse_pnpm_lock(pnpm_v5).unwrap(), locked);
+
+        let yarn = r#"# yarn lockfile v1
+
+"@babel/core@^7.23.0", "@babel/core@^7.23.2":
+  version "7.23.7"
+  resolved "https://registry.yarnpkg.com/@babel/core/-/core-7.23.7.tgz"
+
+lodash@^4.17.21:
+  version "4.17.21"
+"#;
+        let manifest = serde_json::json!({
+            "dependencies": { "lodash": "^4.17.21" },
+            "devDependencies": { "@babel/core": "^7.23.2" }
+        });
+        let locked = parse_yarn_lock(yarn, &manifest);
+        assert_eq!(locked.len(), 2);
+        assert_eq!(locked[1].name(), "@babel/core");
+        assert_eq!(locked[1].version, "7.23.7");
+    }
+
+    #[test]
+    fn test_stale_node_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        let package_lock = serde_json::json!({
+            "lockfileVersion": 3,
+            "packages": {
+                "": { "name": "app" },
+                "node_modules/react": { "version": "18.3.1" },
+                "node_modules/lodash": { "version": "4.17.21" },
+                "node_modules/fsevents": { "version": "2.3.3", "optional": true },
+            }
+        });
+        fs::write(dir.path().join("package-lock.json"), package_lock.to_string()).unwrap();
+        fs::write(dir.path().join("package.json"), r#"{"name": "app"}"#).unwrap();
+        fs::create_dir_all(dir.path().join("node_modules/react")).unwrap();
+        fs::write(dir.path().join("node_modules/react/package.json"), r#"{"version": "18.2.0"}"#).unwrap();
+
+        let check = NodeCheck {
+            path: Some(dir.path().display().to_string()),
+            dependencies: true,
+            engines: false,
+            package_manager: false,
+            when: None,
+        };
+        let results = NodeValidator::new(check.clone()).validate().unwrap();
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+        assert_eq!(
+            results[0].message,
+            "node_modules is out of date with package-lock.json: lodash missing; react 18.2.0 installed, 18.3.1 locked"
+        );
+        assert_eq!(results[0].suggestion.as_deref(), Some("Run `npm install`"));
+
+        fs::create_dir_all(dir.path().join("node_modules/lodash")).unwrap();
+        fs::write(dir.path().join("node_modules/lodash/package.json"), r#"{"version": "4.17.21"}"#).unwrap();
+        fs::write(dir.path().join("node_modules/react/package.json"), r#"{"version": "18.3.1"}"#).unwrap();
+        let results = NodeValidator::new(check.clone()).validate().unwrap();
+        assert_eq!(results[0].message, "node_modules matches package-lock.json (2 packages)");
+
+        // npm's own record of the install takes precedence over each package.json
+        let hidden_lock = serde_json::json!({
+            "lockfileVersion": 3,
+            "packages": { "node_modules/react": { "version": "18.2.0" } }
+        });
+        fs::write(dir.path().join("node_modules/.package-lock.json"), hidden_lock.to_string()).unwrap();
+        let results = NodeValidator::new(check).validate().unwrap();
+        assert_eq!(
+            results[0].message,
+            "node_modules is out of date with package-lock.json: lodash missing; react 18.2.0 installed, 18.3.1 locked"
+        );
+    }
+
+    #[test]
+    fn test_yarn_plug_n_play() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("package.json"), r#"{"name": "app", "dependencies": {"react": "^18"}}"#).unwrap();
+        fs::write(dir.path().join("yarn.lock"), "__metadata:\n  version: 8\n").unwrap();
+        let check = NodeCheck {
+            path: Some(dir.path().display().to_string()),
+            dependencies: true,
+            engines: false,
+            package_manager: false,
+            when: None,
+        };
+        let results = NodeValidator::new(check.clone()).validate().unwrap();
+        assert_eq!(results[0].message, format!("node_modules is missing in {}", dir.path().display()));
+
+        fs::write(dir.path().join(".pnp.cjs"), "").unwrap();
+        let results = NodeValidator::new(check).validate().unwrap();
+        assert!(matches!(results[0].status, ValidationStatus::Skipped));
+    }
+}
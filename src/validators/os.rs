@@ -0,0 +1,349 @@
+use crate::config::{Condition, OsCheck};
+use crate::validators::tool::check_version_requirement;
+use crate::validators::{ValidationResult, Validator};
+use anyhow::Result;
+use regex::Regex;
+use std::env::consts;
+use std::fs;
+
+const OS_RELEASE_PATHS: &[&str] = &["/etc/os-release", "/usr/lib/os-release"];
+
+/// The distribution as described by /etc/os-release.
+#[derive(Debug, Clone, Default)]
+pub struct Distro {
+    pub id: String,
+    pub id_like: Vec<String>,
+    pub version_id: Option<String>,
+    pub pretty_name: String,
+}
+
+/// The platform envcheck is running on, used by the `os` check and `when` conditions.
+#[derive(Debug, Clone, Default)]
+pub struct Platform {
+    pub os: String,
+    pub family: String,
+    pub arch: String,
+    pub distro: Option<Distro>,
+    pub kernel: Option<String>,
+}
+
+impl Platform {
+    pub fn detect() -> Self {
+        Self {
+            os: consts::OS.to_string(),
+            family: consts::FAMILY.to_string(),
+            arch: consts::ARCH.to_string(),
+            distro: OS_RELEASE_PATHS
+                .iter()
+                .find_map(|path| fs::read_to_string(path).ok())
+                .map(|contents| parse_os_release(&contents)),
+            kernel: kernel_release(),
+        }
+    }
+
+    /// Whether the platform satisfies every field set in `condition`.
+    pub fn matches(&self, condition: &Condition) -> bool {
+        (condition.os.is_empty() || condition.os.iter().any(|os| self.is_os(os)))
+            && (condition.arch.is_empty() || condition.arch.iter().any(|arch| self.is_arch(arch)))
+            && (condition.distro.is_empty() || condition.distro.iter().any(|distro| self.is_distro(distro)))
+    }
+
+    fn is_os(&self, name: &str) -> bool {
+        name.eq_ignore_ascii_case(&self.os) || name.eq_ignore_ascii_case(&self.family)
+    }
+
+    fn is_arch(&self, name: &str) -> bool {
+        let name = match name.to_lowercase().as_str() {
+            "amd64" | "x64" => "x86_64".to_string(),
+            "arm64" => "aarch64".to_string(),
+            other => other.to_string(),
+        };
+        name == self.arch
+    }
+
+    fn is_distro(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.distro
+            .as_ref()
+            .is_some_and(|distro| distro.id == name || distro.id_like.contains(&name))
+    }
+}
+
+pub struct OsValidator {
+    check: OsCheck,
+}
+
+impl OsValidator {
+    pub fn new(check: OsCheck) -> Self {
+        Self { check }
+    }
+
+    fn check_platform(&self, platform: &Platform) -> Vec<ValidationResult> {
+        let mut results = Vec::new();
+
+        if !self.check.family.is_empty() {
+            if self.check.family.iter().any(|family| platform.is_os(family)) {
+                results.push(ValidationResult::success(format!(
+                    "Operating system is {} ({})",
+                    platform.os, platform.family
+                )));
+            } else {
+                results.push(ValidationResult::error(
+                    format!(
+                        "Operating system is {}, expected {}",
+                        platform.os,
+                        self.check.family.join(" or ")
+                    ),
+                    Some(format!("This project requires {}", self.check.family.join(" or "))),
+                ));
+            }
+        }
+
+        if !self.check.arch.is_empty() {
+            if self.check.arch.iter().any(|arch| platform.is_arch(arch)) {
+                results.push(ValidationResult::success(format!("Architecture is {}", platform.arch)));
+            } else {
+                results.push(ValidationResult::error(
+                    format!(
+                        "Architecture is {}, expected {}",
+                        platform.arch,
+                        self.check.arch.join(" or ")
+                    ),
+                    Some(format!("This project requires a {} machine", self.check.arch.join(" or "))),
+                ));
+            }
+        }
+
+        if !self.check.distro.is_empty() || self.check.distro_version.is_some() {
+            self.check_distro(platform, &mut results);
+        }
+
+        if let Some(requirement) = &self.check.kernel {
+            match platform.kernel.as_deref() {
+                Some(release) => {
+                    let version = numeric_version(release);
+                    if version.as_deref().is_some_and(|v| satisfies(v, requirement)) {
+                        results.push(ValidationResult::success(format!(
+                            "Kernel {} satisfies {}",
+                            release, requirement
+                        )));
+                    } else {
+                        results.push(ValidationResult::error(
+                            format!("Kernel {} does not satisfy {}", release, requirement),
+                            Some(format!("Upgrade to a kernel {}", requirement)),
+                        ));
+                    }
+                }
+                None => results.push(ValidationResult::warning(
+                    "Could not determine the kernel version".to_string(),
+                    Some(format!("Kernel checks are not supported on {}", platform.os)),
+                )),
+            }
+        }
+
+        results
+    }
+
+    fn check_distro(&self, platform: &Platform, results: &mut Vec<ValidationResult>) {
+        let Some(distro) = &platform.distro else {
+            results.push(ValidationResult::warning(
+                "Could not determine the distribution".to_string(),
+                Some(format!("{} not found", OS_RELEASE_PATHS[0])),
+            ));
+            return;
+        };
+
+        if !self.check.distro.is_empty() {
+            if self.check.distro.iter().any(|name| platform.is_distro(name)) {
+                results.push(ValidationResult::success(format!("Distribution is {}", distro.pretty_name)));
+            } else {
+                results.push(ValidationResult::error(
+                    format!(
+                        "Distribution is {}, expected {}",
+                        distro.pretty_name,
+                        self.check.distro.join(" or ")
+                    ),
+                    Some(format!("This project supports {}", self.check.distro.join(" or "))),
+                ));
+            }
+        }
+
+        if let Some(requirement) = &self.check.distro_version {
+            match distro.version_id.as_deref() {
+                Some(version_id) => {
+                    let version = numeric_version(version_id);
+                    if version.as_deref().is_some_and(|v| satisfies(v, requirement)) {
+                        results.push(ValidationResult::success(format!(
+                            "{} {} satisfies {}",
+                            distro.id, version_id, requirement
+                        )));
+                    } else {
+                        results.push(ValidationResult::error(
+                            format!("{} {} does not satisfy {}", distro.id, version_id, requirement),
+                            Some(format!("Upgrade to {} {}", distro.id, requirement)),
+                        ));
+                    }
+                }
+                None => results.push(ValidationResult::warning(
+                    format!("{} does not report a version", distro.pretty_name),
+                    Some(format!("Remove distro_version or check VERSION_ID in {}", OS_RELEASE_PATHS[0])),
+                )),
+            }
+        }
+    }
+}
+
+impl Validator for OsValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        Ok(self.check_platform(&Platform::detect()))
+    }
+}
+
+/// Parses os-release `KEY=value` lines, where values may be quoted.
+fn parse_os_release(contents: &str) -> Distro {
+    let mut distro = Distro::default();
+    for line in contents.lines() {
+        let Some((key, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches(|c| c == '"' || c == '\'').to_string();
+        match key {
+            "ID" => distro.id = value.to_lowercase(),
+            "ID_LIKE" => distro.id_like = value.split_whitespace().map(|id| id.to_lowercase()).collect(),
+            "VERSION_ID" => distro.version_id = Some(value),
+            "PRETTY_NAME" => distro.pretty_name = value,
+            _ => {}
+        }
+    }
+    if distro.pretty_name.is_empty() {
+        distro.pretty_name = distro.id.clone();
+    }
+    distro
+}
+
+/// Extracts the leading dotted number from versions like "22.04" or
+/// "6.8.0-45-generic", dropping leading zeros so semver can parse it.
+pub(crate) fn numeric_version(version: &str) -> Option<String> {
+    let numeric: Vec<String> = version
+        .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .next()?
+        .split('.')
+        .take_while(|part| !part.is_empty())
+        .map(|part| part.parse::<u64>().map(|n| n.to_string()))
+        .collect::<std::result::Result<_, _>>()
+        .ok()?;
+    (!numeric.is_empty()).then(|| numeric.join("."))
+}
+
+/// Checks a version against a requirement, treating "22.04" in the requirement as 22.4.
+pub(crate) fn satisfies(version: &str, requirement: &str) -> bool {
+    let number = Regex::new(r"\d+").expect("valid regex");
+    let requirement = number.replace_all(requirement, |caps: &regex::Captures| {
+        caps[0]
+            .parse::<u64>()
+            .map(|n| n.to_string())
+            .unwrap_or_else(|_| caps[0].to_string())
+    });
+    check_version_requirement(version, &requirement)
+}
+
+#[cfg(unix)]
+fn kernel_release() -> Option<String> {
+    // SAFETY: uname fills the zeroed struct with NUL-terminated strings
+    let mut uts: libc::utsname = unsafe { std::mem::zeroed() };
+    if unsafe { libc::uname(&mut uts) } != 0 {
+        return None;
+    }
+    let release = unsafe { std::ffi::CStr::from_ptr(uts.release.as_ptr()) };
+    Some(release.to_string_lossy().into_owned())
+}
+
+#[cfg(not(unix))]
+fn kernel_release() -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validators::ValidationStatus;
+
+    const UBUNTU: &str = r#"PRETTY_NAME="Ubuntu 22.04.3 LTS"
+NAME="Ubuntu"
+VERSION_ID="22.04"
+ID=ubuntu
+ID_LIKE=debian
+"#;
+
+    fn ubuntu() -> Platform {
+        Platform {
+            os: "linux".to_string(),
+            family: "unix".to_string(),
+            arch: "x86_64".to_string(),
+            distro: Some(parse_os_release(UBUNTU)),
+            kernel: Some("6.8.0-45-generic".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_os_release() {
+        let distro = parse_os_release(UBUNTU);
+        assert_eq!(distro.id, "ubuntu");
+        assert_eq!(distro.id_like, vec!["debian"]);
+        assert_eq!(distro.version_id.as_deref(), Some("22.04"));
+        assert_eq!(distro.pretty_name, "Ubuntu 22.04.3 LTS");
+    }
+
+    #[test]
+    fn test_versions() {
+        assert_eq!(numeric_version("22.04").as_deref(), Some("22.4"));
+        assert_eq!(numeric_version("6.8.0-45-generic").as_deref(), Some("6.8.0"));
+        assert_eq!(numeric_version("rolling"), None);
+        assert!(satisfies("22.4", ">=20.04"));
+        assert!(!satisfies("22.4", ">=22.10"));
+        assert!(satisfies("6.8.0", ">=5.10"));
+    }
+
+    #[test]
+    fn test_os_check() {
+        let check = OsCheck {
+            family: vec!["linux".to_string()],
+            distro: vec!["debian".to_string()],
+            distro_version: Some(">=20.04".to_string()),
+            kernel: Some(">=5.10".to_string()),
+            arch: vec!["amd64".to_string()],
+            ..Default::default()
+        };
+        let results = OsValidator::new(check).check_platform(&ubuntu());
+        assert_eq!(results.len(), 5);
+        assert!(results.iter().all(|r| matches!(r.status, ValidationStatus::Success)));
+        assert_eq!(results[3].message, "ubuntu 22.04 satisfies >=20.04");
+
+        let check = OsCheck {
+            family: vec!["macos".to_string()],
+            arch: vec!["aarch64".to_string()],
+            kernel: Some(">=7".to_string()),
+            ..Default::default()
+        };
+        let results = OsValidator::new(check).check_platform(&ubuntu());
+        assert!(results.iter().all(|r| matches!(r.status, ValidationStatus::Error)));
+        assert_eq!(results[0].message, "Operating system is linux, expected macos");
+        assert_eq!(results[1].message, "Architecture is x86_64, expected aarch64");
+        assert_eq!(results[2].message, "Kernel 6.8.0-45-generic does not satisfy >=7");
+    }
+
+    #[test]
+    fn test_conditions() {
+        let platform = ubuntu();
+        let condition = |os: &[&str], arch: &[&str]| Condition {
+            os: os.iter().map(|s| s.to_string()).collect(),
+            arch: arch.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        };
+        assert!(platform.matches(&Condition::default()));
+        assert!(platform.matches(&condition(&["linux"], &["x86_64", "aarch64"])));
+        assert!(platform.matches(&condition(&["unix"], &[])));
+        assert!(!platform.matches(&condition(&["linux"], &["aarch64"])));
+        assert!(!platform.matches(&condition(&["windows"], &[])));
+    }
+}
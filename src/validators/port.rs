@@ -1,35 +1,154 @@
+use crate::config::PortCheck;
+use crate::validators::retry::with_retries;
 use crate::validators::{ValidationResult, Validator};
 use anyhow::Result;
-use std::net::TcpListener;
+use std::io;
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+const DEFAULT_HOST: &str = "127.0.0.1";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct PortValidator {
-    port: u16,
+    check: PortCheck,
 }
 
 impl PortValidator {
-    pub fn new(port: u16) -> Self {
-        Self { port }
+    /// Takes a full check, or a bare port number to check that it is available.
+    pub fn new(check: impl Into<PortCheck>) -> Self {
+        Self { check: check.into() }
+    }
+
+    fn host(&self) -> &str {
+        self.check.host.as_deref().unwrap_or(DEFAULT_HOST)
+    }
+
+    /// The `host:port` this check connects to, if it is on another machine.
+    pub fn remote_target(&self) -> Option<String> {
+        let host = self.host();
+        (self.check.listening && !is_local_host(host)).then(|| format!("{}:{}", host, self.check.port))
+    }
+
+    fn check_available(&self) -> Vec<ValidationResult> {
+        let port = self.check.port;
+        match TcpListener::bind((self.host(), port)) {
+            Ok(_) => vec![ValidationResult::success(
+                format!("Port {} is available", port),
+            )],
+            Err(_) => vec![ValidationResult::error(
+                format!("Port {} is already in use", port),
+                Some(format!("Free up port {} or change the port in your config", port)),
+            )],
+        }
+    }
+
+    fn check_listening(&self) -> Vec<ValidationResult> {
+        let (host, port) = (self.host(), self.check.port);
+        let timeout = self.check.retry.timeout.unwrap_or(DEFAULT_TIMEOUT);
+
+        let addrs = match (host, port).to_socket_addrs() {
+            Ok(addrs) => addrs,
+            Err(e) => {
+                return vec![ValidationResult::error(
+                    format!("Failed to resolve {}", host),
+                    Some(format!("Error: {}", e)),
+                )]
+            }
+        };
+
+        if let Err(e) = connect_any(addrs, timeout) {
+            return vec![ValidationResult::error(
+                format!("Nothing is listening on {}:{}", host, port),
+                Some(format!("Start the service for port {} ({})", port, e)),
+            )];
+        }
+
+        vec![ValidationResult::success(format!(
+            "Port {} is listening on {}",
+            port, host
+        ))]
     }
 }
 
+/// Connects to the first of `addrs` that accepts a connection within `timeout`.
+pub(crate) fn connect_any(
+    addrs: impl IntoIterator<Item = SocketAddr>,
+    timeout: Duration,
+) -> io::Result<TcpStream> {
+    let mut last_error = None;
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no addresses found")))
+}
+
+/// Whether `host` names this machine, so reaching it needs no network access.
+pub(crate) fn is_local_host(host: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost")
+        || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback() || ip.is_unspecified())
+}
+
 impl Validator for PortValidator {
     fn validate(&self) -> Result<Vec<ValidationResult>> {
-        let mut results = Vec::new();
-
-        match TcpListener::bind(format!("127.0.0.1:{}", self.port)) {
-            Ok(_) => {
-                results.push(ValidationResult::success(
-                    format!("Port {} is available", self.port),
-                ));
-            }
-            Err(_) => {
-                results.push(ValidationResult::error(
-                    format!("Port {} is already in use", self.port),
-                    Some(format!("Free up port {} or change the port in your config", self.port)),
-                ));
+        Ok(with_retries(&self.check.retry, || {
+            if self.check.listening {
+                self.check_listening()
+            } else {
+                self.check_available()
             }
-        }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RetryConfig;
+    use crate::validators::ValidationStatus;
+
+    #[test]
+    fn test_listening_probe() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let check = PortCheck { port, listening: true, ..Default::default() };
+        let results = PortValidator::new(check).validate().unwrap();
+        assert!(matches!(results[0].status, ValidationStatus::Success));
+
+        let results = PortValidator::new(port).validate().unwrap();
+        assert_eq!(results[0].message, format!("Port {} is already in use", port));
+
+        drop(listener);
+        let check = PortCheck {
+            port,
+            listening: true,
+            retry: RetryConfig {
+                retries: 1,
+                retry_interval: Some(Duration::from_millis(1)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let results = PortValidator::new(check).validate().unwrap();
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+        assert_eq!(
+            results[0].message,
+            format!("Nothing is listening on 127.0.0.1:{} (after 2 attempts)", port)
+        );
+    }
+
+    #[test]
+    fn test_remote_target() {
+        assert!(is_local_host("localhost") && is_local_host("[::1]") && is_local_host("0.0.0.0"));
+        assert!(!is_local_host("db.example.com") && !is_local_host("10.0.0.5"));
 
-        Ok(results)
+        let check = PortCheck { port: 5432, host: Some("10.0.0.5".to_string()), listening: true, ..Default::default() };
+        assert_eq!(PortValidator::new(check).remote_target(), Some("10.0.0.5:5432".to_string()));
+        let check = PortCheck { port: 5432, listening: true, ..Default::default() };
+        assert_eq!(PortValidator::new(check).remote_target(), None);
     }
 }
@@ -0,0 +1,279 @@
+use crate::config::ProcessCheck;
+use crate::validators::{ValidationResult, Validator};
+use anyhow::Result;
+use regex::Regex;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const PROC_PATH: &str = "/proc";
+const PASSWD_PATH: &str = "/etc/passwd";
+
+/// A running process as seen in /proc.
+#[derive(Debug, Clone, PartialEq)]
+struct ProcessInfo {
+    pid: u32,
+    comm: String,
+    cmdline: String,
+    uid: u32,
+}
+
+impl ProcessInfo {
+    /// Whether `name` is the process name or the basename of argv[0].
+    /// The process name is truncated to 15 bytes by the kernel.
+    fn has_name(&self, name: &str) -> bool {
+        let argv0 = self.cmdline.split(' ').next().unwrap_or_default();
+        let basename = argv0.rsplit('/').next().unwrap_or_default();
+        self.comm == name || basename == name
+    }
+}
+
+pub struct ProcessValidator {
+    check: ProcessCheck,
+}
+
+impl ProcessValidator {
+    pub fn new(check: ProcessCheck) -> Self {
+        Self { check }
+    }
+
+    fn label(&self) -> String {
+        match (&self.check.name, &self.check.matches) {
+            (Some(name), _) => name.clone(),
+            (None, Some(pattern)) => format!("process matching '{}'", pattern),
+            (None, None) => "process".to_string(),
+        }
+    }
+
+    fn check_processes(&self, processes: &[ProcessInfo], passwd: &str) -> ValidationResult {
+        let label = self.label();
+        if self.check.name.is_none() && self.check.matches.is_none() {
+            return ValidationResult::error(
+                "Process check in config has neither name nor matches".to_string(),
+                Some("Add a name like postgres or a matches regex".to_string()),
+            );
+        }
+
+        let pattern = self.check.matches.as_deref().map(Regex::new);
+        let matching: Vec<&ProcessInfo> = processes
+            .iter()
+            .filter(|process| self.check.name.as_deref().is_none_or(|name| process.has_name(name)))
+            .filter(|process| match (&pattern, self.check.matches.as_deref()) {
+                (Some(Ok(re)), _) => re.is_match(&process.cmdline),
+                // Fallback to substring if regex is invalid
+                (Some(Err(_)), Some(pattern)) => process.cmdline.contains(pattern),
+                _ => true,
+            })
+            .collect();
+
+        let owned: Vec<&ProcessInfo> = match &self.check.user {
+            Some(user) => {
+                let Some(uid) = resolve_uid(user, passwd) else {
+                    return ValidationResult::error(
+                        format!("Unknown user '{}' in process check", user),
+                        Some(format!("Use a user name from {} or a numeric uid", PASSWD_PATH)),
+                    );
+                };
+                matching.iter().copied().filter(|process| process.uid == uid).collect()
+            }
+            None => matching.clone(),
+        };
+
+        let owner = self
+            .check
+            .user
+            .as_ref()
+            .map(|user| format!(" as {}", user))
+            .unwrap_or_default();
+        let count = owned.len();
+        if count >= self.check.min_count {
+            let pids: Vec<String> = owned.iter().take(5).map(|process| process.pid.to_string()).collect();
+            let more = if count > pids.len() { ", ..." } else { "" };
+            return ValidationResult::success(format!(
+                "{} is running{} (pid {}{})",
+                label,
+                owner,
+                pids.join(", "),
+                more
+            ));
+        }
+
+        let message = if count == 0 {
+            format!("{} is not running{}", label, owner)
+        } else {
+            let plural = if count == 1 { "" } else { "es" };
+            let found = match (&self.check.name, &self.check.matches) {
+                (Some(name), _) => format!("{} process{}", name, plural),
+                (None, pattern) => format!("process{} matching '{}'", plural, pattern.as_deref().unwrap_or_default()),
+            };
+            format!("Found {} {}{}, need at least {}", count, found, owner, self.check.min_count)
+        };
+        let suggestion = if matching.len() > owned.len() {
+            let mut users: Vec<String> = matching
+                .iter()
+                .filter(|process| !owned.contains(process))
+                .map(|process| user_name(process.uid, passwd))
+                .collect();
+            users.sort();
+            users.dedup();
+            format!("{} is running as {} instead", label, users.join(", "))
+        } else {
+            format!("Start {} and rerun envcheck", label)
+        };
+        ValidationResult::error(message, Some(suggestion))
+    }
+}
+
+impl Validator for ProcessValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        let processes = match scan(Path::new(PROC_PATH)) {
+            Ok(processes) => processes,
+            Err(e) => {
+                return Ok(vec![ValidationResult::warning(
+                    format!("Could not list processes to check {}", self.label()),
+                    Some(format!("Process checks read {} and only work on Linux (Error: {})", PROC_PATH, e)),
+                )])
+            }
+        };
+        let passwd = fs::read_to_string(PASSWD_PATH).unwrap_or_default();
+        Ok(vec![self.check_processes(&processes, &passwd)])
+    }
+}
+
+/// Lists processes from a procfs mount, skipping ones that exit mid-scan
+/// or whose details aren't readable.
+fn scan(root: &Path) -> io::Result<Vec<ProcessInfo>> {
+    let mut processes = Vec::new();
+    for entry in fs::read_dir(root)?.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse().ok()) else {
+            continue;
+        };
+        let dir = entry.path();
+        let (Ok(comm), Ok(cmdline), Ok(uid)) = (
+            fs::read_to_string(dir.join("comm")),
+            fs::read(dir.join("cmdline")),
+            owner(&dir),
+        ) else {
+            continue;
+        };
+        let cmdline = String::from_utf8_lossy(&cmdline)
+            .trim_end_matches('\0')
+            .replace('\0', " ");
+        processes.push(ProcessInfo {
+            pid,
+            comm: comm.trim_end().to_string(),
+            cmdline,
+            uid,
+        });
+    }
+    processes.sort_by_key(|process| process.pid);
+    Ok(processes)
+}
+
+/// Reads the effective uid from the `Uid:` line of /proc/<pid>/status. The owner of the
+/// /proc/<pid> directory itself is root for non-dumpable processes like ssh-agent.
+fn owner(dir: &Path) -> io::Result<u32> {
+    let status = fs::read_to_string(dir.join("status"))?;
+    parse_uid(&status).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no Uid line in status"))
+}
+
+/// Parses `Uid:\treal\teffective\tsaved\tfs` and returns the effective uid.
+fn parse_uid(status: &str) -> Option<u32> {
+    let line = status.lines().find_map(|line| line.strip_prefix("Uid:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Resolves a user name or numeric uid using passwd-format lines.
+fn resolve_uid(user: &str, passwd: &str) -> Option<u32> {
+    user.parse().ok().or_else(|| {
+        passwd.lines().find_map(|line| {
+            let mut fields = line.split(':');
+            (fields.next()? == user).then(|| fields.nth(1)?.parse().ok())?
+        })
+    })
+}
+
+fn user_name(uid: u32, passwd: &str) -> String {
+    passwd
+        .lines()
+        .find_map(|line| {
+            let fields: Vec<&str> = line.split(':').collect();
+            (fields.get(2)? == &uid.to_string()).then(|| fields[0].to_string())
+        })
+        .unwrap_or_else(|| format!("uid {}", uid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validators::ValidationStatus;
+
+    const PASSWD: &str = "root:x:0:0:root:/root:/bin/bash\npostgres:x:999:999::/var/lib/postgresql:/bin/sh\n";
+
+    fn process(pid: u32, comm: &str, cmdline: &str, uid: u32) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            comm: comm.to_string(),
+            cmdline: cmdline.to_string(),
+            uid,
+        }
+    }
+
+    fn check(name: Option<&str>, matches: Option<&str>, min_count: usize, user: Option<&str>) -> ProcessCheck {
+        ProcessCheck {
+            name: name.map(str::to_string),
+            matches: matches.map(str::to_string),
+            min_count,
+            user: user.map(str::to_string),
+            when: None,
+        }
+    }
+
+    #[test]
+    fn test_process_matching() {
+        let processes = vec![
+            process(10, "postgres", "/usr/lib/postgresql/16/bin/postgres -D /var/lib/postgresql/16/main", 999),
+            process(11, "postgres", "postgres: 16/main: checkpointer", 999),
+            process(12, "postgres", "postgres: 16/main: background writer", 999),
+            process(20, "ssh-agent", "ssh-agent -s", 1000),
+        ];
+
+        let result = ProcessValidator::new(check(Some("postgres"), None, 1, Some("postgres")))
+            .check_processes(&processes, PASSWD);
+        assert!(matches!(result.status, ValidationStatus::Success));
+        assert_eq!(result.message, "postgres is running as postgres (pid 10, 11, 12)");
+
+        let result = ProcessValidator::new(check(None, Some("postgres: .* writer"), 2, None))
+            .check_processes(&processes, PASSWD);
+        assert_eq!(
+            result.message,
+            "Found 1 process matching 'postgres: .* writer', need at least 2"
+        );
+
+        let result = ProcessValidator::new(check(Some("ssh-agent"), None, 1, Some("root")))
+            .check_processes(&processes, PASSWD);
+        assert_eq!(result.message, "ssh-agent is not running as root");
+        assert_eq!(result.suggestion.as_deref(), Some("ssh-agent is running as uid 1000 instead"));
+
+        let result = ProcessValidator::new(check(Some("redis-server"), None, 1, None)).check_processes(&processes, PASSWD);
+        assert_eq!(result.message, "redis-server is not running");
+    }
+
+    #[test]
+    fn test_scan() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("42");
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("comm"), "node\n").unwrap();
+        fs::write(dir.join("cmdline"), "node\0server.js\0").unwrap();
+        fs::write(dir.join("status"), "Name:\tnode\nUid:\t1000\t1001\t1001\t1001\nGid:\t100\t100\t100\t100\n").unwrap();
+        fs::create_dir(root.path().join("self")).unwrap();
+
+        let processes = scan(root.path()).unwrap();
+        assert_eq!(processes.len(), 1);
+        assert_eq!((processes[0].pid, processes[0].comm.as_str()), (42, "node"));
+        assert_eq!(processes[0].uid, 1001);
+        assert_eq!(processes[0].cmdline, "node server.js");
+        assert!(processes[0].has_name("node"));
+    }
+}
@@ -0,0 +1,193 @@
+use crate::config::NetworkCheck;
+use crate::expand::expand_vars;
+use reqwest::Url;
+use std::env;
+use std::net::IpAddr;
+
+/// Proxies to use for a network check, combining its `proxy` and `no_proxy`
+/// settings with `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY`.
+#[derive(Debug, Clone, Default)]
+pub struct ProxySettings {
+    http: Option<Url>,
+    https: Option<Url>,
+    no_proxy: Vec<String>,
+}
+
+impl ProxySettings {
+    pub fn from_check(check: &NetworkCheck) -> Result<Self, String> {
+        Self::resolve(check, |name| env::var(name).ok())
+    }
+
+    /// Resolves the settings, reading environment variables through `lookup`.
+    /// An explicit `proxy` in the config takes precedence over the environment.
+    fn resolve(check: &NetworkCheck, lookup: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let var = |names: &[&str]| {
+            names
+                .iter()
+                .filter_map(|name| lookup(name).or_else(|| lookup(&name.to_lowercase())))
+                .find(|value| !value.is_empty())
+        };
+
+        let (http, https) = match &check.proxy {
+            Some(proxy) => {
+                let proxy = expand_vars(proxy).map_err(|e| format!("Invalid proxy: {}", e))?;
+                let proxy = parse_proxy(&proxy)?;
+                (Some(proxy.clone()), Some(proxy))
+            }
+            None => (
+                var(&["HTTP_PROXY", "ALL_PROXY"]).map(|p| parse_proxy(&p)).transpose()?,
+                var(&["HTTPS_PROXY", "ALL_PROXY"]).map(|p| parse_proxy(&p)).transpose()?,
+            ),
+        };
+
+        let mut no_proxy = check.no_proxy.clone();
+        if let Some(from_env) = var(&["NO_PROXY"]) {
+            no_proxy.extend(from_env.split(',').map(|entry| entry.trim().to_string()));
+        }
+        no_proxy.retain(|entry| !entry.is_empty());
+
+        Ok(Self { http, https, no_proxy })
+    }
+
+    /// Returns the proxy to use for `url`, or `None` to connect directly.
+    pub fn proxy_for(&self, url: &Url) -> Option<Url> {
+        let host = url.host_str()?;
+        let port = url.port_or_known_default();
+        if self.no_proxy.iter().any(|entry| bypasses(entry, host, port)) {
+            return None;
+        }
+        match url.scheme() {
+            "https" => self.https.clone(),
+            _ => self.http.clone(),
+        }
+    }
+}
+
+fn parse_proxy(proxy: &str) -> Result<Url, String> {
+    let with_scheme = if proxy.contains("://") {
+        proxy.to_string()
+    } else {
+        format!("http://{}", proxy)
+    };
+    Url::parse(&with_scheme).map_err(|e| format!("Invalid proxy '{}': {}", proxy, e))
+}
+
+/// Whether a `no_proxy` entry covers `host`: `*` matches everything, a domain
+/// matches itself and its subdomains, an IP address matches exactly and a CIDR
+/// block like `10.0.0.0/8` matches the addresses in it. An entry with a port,
+/// like `localhost:8080`, only matches that port.
+fn bypasses(entry: &str, host: &str, port: Option<u16>) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']').to_lowercase();
+    let mut entry = entry.to_lowercase();
+    if entry == "*" {
+        return true;
+    }
+    if entry.parse::<IpAddr>().is_err() {
+        if let Some((name, entry_port)) = entry.rsplit_once(':') {
+            match entry_port.parse::<u16>() {
+                Ok(entry_port) if Some(entry_port) == port => entry = name.to_string(),
+                Ok(_) => return false,
+                Err(_) => {}
+            }
+        }
+    }
+    let entry = entry.trim_start_matches('[').trim_end_matches(']');
+
+    if let Some((network, prefix)) = entry.split_once('/') {
+        return match (network.parse::<IpAddr>(), prefix.parse::<u32>(), host.parse::<IpAddr>()) {
+            (Ok(network), Ok(prefix), Ok(host)) => in_network(host, network, prefix),
+            _ => false,
+        };
+    }
+    if let (Ok(entry), Ok(host)) = (entry.parse::<IpAddr>(), host.parse::<IpAddr>()) {
+        return entry == host;
+    }
+    let domain = entry.trim_start_matches("*.").trim_start_matches('.');
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+/// Whether `ip` is inside `network/prefix`; addresses of different families never match.
+fn in_network(ip: IpAddr, network: IpAddr, prefix: u32) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) if prefix <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) if prefix <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn resolve(check: &NetworkCheck, vars: &[(&str, &str)]) -> ProxySettings {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        ProxySettings::resolve(check, |name| vars.get(name).cloned()).unwrap()
+    }
+
+    fn proxy_for(settings: &ProxySettings, url: &str) -> Option<String> {
+        settings
+            .proxy_for(&Url::parse(url).unwrap())
+            .map(|proxy| proxy.to_string())
+    }
+
+    #[test]
+    fn test_proxy_from_environment() {
+        let settings = resolve(
+            &NetworkCheck::default(),
+            &[
+                ("http_proxy", "proxy.corp:3128"),
+                ("HTTPS_PROXY", "http://secure.corp:8080"),
+                ("NO_PROXY", "localhost, .internal,10.0.0.1"),
+            ],
+        );
+        assert_eq!(proxy_for(&settings, "http://example.com"), Some("http://proxy.corp:3128/".to_string()));
+        assert_eq!(proxy_for(&settings, "https://example.com"), Some("http://secure.corp:8080/".to_string()));
+        assert_eq!(proxy_for(&settings, "http://localhost:3000"), None);
+        assert_eq!(proxy_for(&settings, "https://vault.internal"), None);
+        assert_eq!(proxy_for(&settings, "https://internal"), None);
+        assert_eq!(proxy_for(&settings, "http://10.0.0.1/health"), None);
+        assert!(proxy_for(&settings, "http://notinternal.com").is_some());
+    }
+
+    #[test]
+    fn test_explicit_proxy_overrides_environment() {
+        let check = NetworkCheck {
+            proxy: Some("http://explicit:8080".to_string()),
+            no_proxy: vec!["*.test".to_string()],
+            ..Default::default()
+        };
+        let settings = resolve(&check, &[("HTTPS_PROXY", "http://env:3128"), ("no_proxy", "corp.example")]);
+        assert_eq!(proxy_for(&settings, "https://example.com"), Some("http://explicit:8080/".to_string()));
+        assert_eq!(proxy_for(&settings, "https://api.myapp.test"), None);
+        assert_eq!(proxy_for(&settings, "https://git.corp.example"), None);
+
+        let check = NetworkCheck { proxy: Some("http://[bad".to_string()), ..Default::default() };
+        assert!(ProxySettings::resolve(&check, |_| None).is_err());
+    }
+
+    #[test]
+    fn test_no_proxy_cidr_and_ports() {
+        let settings = resolve(
+            &NetworkCheck::default(),
+            &[("HTTP_PROXY", "proxy.corp:3128"), ("NO_PROXY", "10.0.0.0/8,fd00::/8,localhost:8080,[::1]:9000")],
+        );
+        assert_eq!(proxy_for(&settings, "http://10.20.30.40/health"), None);
+        assert!(proxy_for(&settings, "http://11.0.0.1/health").is_some());
+        assert_eq!(proxy_for(&settings, "http://[fd12::1]/"), None);
+        assert_eq!(proxy_for(&settings, "http://localhost:8080/"), None);
+        assert!(proxy_for(&settings, "http://localhost:3000/").is_some());
+        assert_eq!(proxy_for(&settings, "http://[::1]:9000/"), None);
+        assert!(bypasses("0.0.0.0/0", "192.168.1.1", None));
+        assert!(!bypasses("10.0.0.0/33", "10.0.0.1", None));
+    }
+}
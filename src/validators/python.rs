@@ -0,0 +1,617 @@
+use crate::config::PythonCheck;
+use crate::expand::expand_path;
+use crate::validators::os::{numeric_version, satisfies};
+use crate::validators::{ValidationResult, Validator};
+use anyhow::Result;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const VENV_DIRS: &[&str] = &[".venv", "venv"];
+const REQUIREMENT_FILES: &[&str] = &["requirements.txt", "pyproject.toml"];
+
+/// A pinned or bounded dependency, e.g. `django` with specifier `>=4.2,<5`.
+#[derive(Debug, Clone, PartialEq)]
+struct Requirement {
+    name: String,
+    specifier: String,
+}
+
+pub struct PythonValidator {
+    check: PythonCheck,
+}
+
+impl PythonValidator {
+    pub fn new(check: PythonCheck) -> Self {
+        Self { check }
+    }
+
+    /// Runs every check against the virtualenv, given the `VIRTUAL_ENV` of the current shell.
+    fn check_in(&self, active: Option<PathBuf>) -> Vec<ValidationResult> {
+        let mut results = Vec::new();
+
+        let configured = match self.check.venv.as_deref().map(expand_path).transpose() {
+            Ok(configured) => configured.map(PathBuf::from),
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Invalid venv path in config: {}", e),
+                    Some(e.suggestion()),
+                ));
+                return results;
+            }
+        };
+        let venv = configured.clone().or_else(|| active.clone()).or_else(|| {
+            VENV_DIRS
+                .iter()
+                .map(PathBuf::from)
+                .find(|dir| dir.join("pyvenv.cfg").is_file())
+        });
+        let Some(venv) = venv else {
+            results.push(ValidationResult::error(
+                "No virtualenv found (VIRTUAL_ENV is not set and there is no .venv directory)".to_string(),
+                Some("Create one with `python3 -m venv .venv` and activate it with `source .venv/bin/activate`".to_string()),
+            ));
+            return results;
+        };
+        let shown = venv.display().to_string();
+
+        let Ok(cfg) = fs::read_to_string(venv.join("pyvenv.cfg")) else {
+            results.push(ValidationResult::error(
+                format!("{} is not a virtualenv (no pyvenv.cfg)", shown),
+                Some(format!("Create it with `python3 -m venv {}`", shown)),
+            ));
+            return results;
+        };
+
+        match &active {
+            None => results.push(ValidationResult::warning(
+                format!("Virtualenv {} is not activated", shown),
+                Some(format!("Run `source {}`", activate_script(&venv).display())),
+            )),
+            Some(active) if !same_path(active, &venv) => results.push(ValidationResult::warning(
+                format!("Active virtualenv {} is not {}", active.display(), shown),
+                Some(format!("Run `deactivate && source {}`", activate_script(&venv).display())),
+            )),
+            Some(_) => results.push(ValidationResult::success(format!("Virtualenv {} is active", shown))),
+        }
+
+        if !self.check_interpreter(&venv, &cfg, &mut results) {
+            return results;
+        }
+
+        let files: Vec<String> = if self.check.requirements.is_empty() {
+            REQUIREMENT_FILES
+                .iter()
+                .filter(|file| Path::new(file).is_file())
+                .map(|file| file.to_string())
+                .collect()
+        } else {
+            self.check.requirements.clone()
+        };
+        if files.is_empty() {
+            return results;
+        }
+
+        let installed = installed_packages(&venv);
+        for file in &files {
+            self.check_requirements(file, &venv, &installed, &mut results);
+        }
+        results
+    }
+
+    /// Checks the venv's interpreter; returns false if it is unusable.
+    fn check_interpreter(&self, venv: &Path, cfg: &str, results: &mut Vec<ValidationResult>) -> bool {
+        let shown = venv.display();
+        let python = interpreter(venv);
+        if !python.exists() {
+            let broken = python.symlink_metadata().is_ok();
+            results.push(ValidationResult::error(
+                if broken {
+                    format!("The interpreter in {} is a broken link", shown)
+                } else {
+                    format!("The interpreter in {} is missing", shown)
+                },
+                Some(format!(
+                    "The base Python was probably removed or upgraded; recreate the virtualenv with `python3 -m venv --clear {}`",
+                    shown
+                )),
+            ));
+            return false;
+        }
+
+        let version = parse_pyvenv_version(cfg);
+        match (&self.check.version, &version) {
+            (Some(requirement), Some(version)) => {
+                if numeric_version(version).is_some_and(|v| satisfies(&v, requirement)) {
+                    results.push(ValidationResult::success(format!(
+                        "{} uses Python {} (satisfies {})",
+                        shown, version, requirement
+                    )));
+                } else {
+                    results.push(ValidationResult::error(
+                        format!("{} uses Python {}, which does not satisfy {}", shown, version, requirement),
+                        Some(format!(
+                            "Recreate it with a matching interpreter, e.g. `python{} -m venv --clear {}`",
+                            numeric_version(requirement.trim_start_matches(|c: char| !c.is_ascii_digit()))
+                                .unwrap_or_else(|| "3".to_string()),
+                            shown
+                        )),
+                    ));
+                }
+            }
+            (Some(requirement), None) => results.push(ValidationResult::warning(
+                format!("Could not determine the Python version of {}", shown),
+                Some(format!("pyvenv.cfg has no version; run `{} --version` to compare with {}", python.display(), requirement)),
+            )),
+            (None, Some(version)) => results.push(ValidationResult::success(format!("{} uses Python {}", shown, version))),
+            (None, None) => results.push(ValidationResult::success(format!("{} has an interpreter", shown))),
+        }
+        true
+    }
+
+    fn check_requirements(
+        &self,
+        file: &str,
+        venv: &Path,
+        installed: &HashMap<String, String>,
+        results: &mut Vec<ValidationResult>,
+    ) {
+        let path = match expand_path(file) {
+            Ok(path) => PathBuf::from(path),
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Invalid requirements path in config: {}", e),
+                    Some(e.suggestion()),
+                ));
+                return;
+            }
+        };
+        let requirements = if path.extension().is_some_and(|ext| ext == "toml") {
+            fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|contents| parse_pyproject(&contents))
+        } else {
+            parse_requirements_file(&path, 0)
+        };
+        let requirements = match requirements {
+            Ok(requirements) => requirements,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Cannot read {}: {}", file, e),
+                    Some("Check the requirements path in the config".to_string()),
+                ));
+                return;
+            }
+        };
+
+        let pip = venv.join(if cfg!(windows) { "Scripts" } else { "bin" }).join("pip");
+        let install = if path.extension().is_some_and(|ext| ext == "toml") {
+            format!("Run `{} install -e .` or your project's sync command (`poetry install`, `uv sync`)", pip.display())
+        } else {
+            format!("Run `{} install -r {}`", pip.display(), file)
+        };
+
+        let mut failed = false;
+        for requirement in &requirements {
+            match installed.get(&requirement.name) {
+                None => {
+                    failed = true;
+                    results.push(ValidationResult::error(
+                        format!("Package {} from {} is not installed", requirement.name, file),
+                        Some(install.clone()),
+                    ));
+                }
+                Some(version) if !matches_specifier(version, &requirement.specifier) => {
+                    failed = true;
+                    results.push(ValidationResult::error(
+                        format!(
+                            "{} {} is installed, but {} requires {}",
+                            requirement.name, version, file, requirement.specifier
+                        ),
+                        Some(install.clone()),
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+        if !failed {
+            results.push(ValidationResult::success(format!(
+                "All {} packages from {} are installed",
+                requirements.len(),
+                file
+            )));
+        }
+    }
+}
+
+impl Validator for PythonValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        let active = env::var_os("VIRTUAL_ENV").filter(|v| !v.is_empty()).map(PathBuf::from);
+        Ok(self.check_in(active))
+    }
+}
+
+fn interpreter(venv: &Path) -> PathBuf {
+    if cfg!(windows) {
+        venv.join("Scripts").join("python.exe")
+    } else {
+        venv.join("bin").join("python")
+    }
+}
+
+fn activate_script(venv: &Path) -> PathBuf {
+    if cfg!(windows) {
+        venv.join("Scripts").join("activate")
+    } else {
+        venv.join("bin").join("activate")
+    }
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Reads the interpreter version from pyvenv.cfg (`version` from venv,
+/// `version_info` from virtualenv and uv).
+fn parse_pyvenv_version(cfg: &str) -> Option<String> {
+    let values: HashMap<&str, &str> = cfg
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect();
+    let version = values.get("version").or_else(|| values.get("version_info"))?;
+    // version_info looks like 3.12.1.final.0
+    let release: Vec<&str> = version.split('.').take_while(|part| part.parse::<u64>().is_ok()).collect();
+    (!release.is_empty()).then(|| release.join("."))
+}
+
+/// Maps normalized package names to installed versions by reading the
+/// metadata of every distribution in the venv's site-packages.
+fn installed_packages(venv: &Path) -> HashMap<String, String> {
+    let mut site_packages = vec![venv.join("Lib").join("site-packages")];
+    let pattern = venv.join("lib").join("python*").join("site-packages");
+    if let Ok(paths) = glob::glob(&pattern.to_string_lossy()) {
+        site_packages.extend(paths.flatten());
+    }
+
+    let mut installed = HashMap::new();
+    for entry in site_packages.iter().filter_map(|dir| fs::read_dir(dir).ok()).flatten().flatten() {
+        let path = entry.path();
+        let metadata = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("dist-info") => path.join("METADATA"),
+            Some("egg-info") => path.join("PKG-INFO"),
+            _ => continue,
+        };
+        let Ok(contents) = fs::read_to_string(metadata) else {
+            continue;
+        };
+        let header = |key: &str| {
+            contents
+                .lines()
+                .take_while(|line| !line.is_empty())
+                .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+                .map(|value| value.trim().to_string())
+        };
+        if let (Some(name), Some(version)) = (header("Name"), header("Version")) {
+            installed.insert(normalize_name(&name), version);
+        }
+    }
+    installed
+}
+
+/// Normalizes a package name as pip does: lowercase, with runs of `-`, `_` and `.` as `-`.
+fn normalize_name(name: &str) -> String {
+    let mut normalized = String::new();
+    for c in name.trim().chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+        }
+    }
+    normalized
+}
+
+/// Parses a PEP 508 requirement such as `celery[redis]~=5.3`. Requirements
+/// with environment markers are skipped, since they may not apply here.
+fn parse_requirement(line: &str) -> Option<Requirement> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('-') || line.contains(';') {
+        return None;
+    }
+    let name_len = line
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(line.len());
+    if name_len == 0 {
+        return None;
+    }
+    let mut rest = line[name_len..].trim_start();
+    if rest.starts_with('[') {
+        rest = rest.split_once(']').map_or("", |(_, rest)| rest);
+    }
+    let specifier = if rest.trim_start().starts_with('@') {
+        String::new()
+    } else {
+        rest.trim().trim_start_matches('(').trim_end_matches(')').replace(' ', "")
+    };
+    Some(Requirement {
+        name: normalize_name(&line[..name_len]),
+        specifier,
+    })
+}
+
+/// Parses a requirements file, following `-r other.txt` includes.
+fn parse_requirements_file(path: &Path, depth: usize) -> Result<Vec<Requirement>, String> {
+    let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut requirements = Vec::new();
+    for line in contents.replace("\\\n", " ").lines() {
+        let line = line.split(" #").next().unwrap_or_default().trim();
+        let line = if line.starts_with('#') { "" } else { line };
+        let include = line
+            .strip_prefix("-r")
+            .or_else(|| line.strip_prefix("--requirement"))
+            .map(|rest| rest.trim_start_matches('=').trim());
+        match include {
+            Some(include) if depth < 8 => {
+                let include = path.parent().unwrap_or(Path::new(".")).join(include);
+                requirements.extend(parse_requirements_file(&include, depth + 1)?);
+            }
+            Some(_) => return Err("requirements files include each other too deeply".to_string()),
+            // Options such as --hash follow the requirement on the same line
+            None => requirements.extend(parse_requirement(line.split(" --").next().unwrap_or_default())),
+        }
+    }
+    Ok(requirements)
+}
+
+/// Reads `[project].dependencies` (PEP 621) and `[tool.poetry.dependencies]`.
+fn parse_pyproject(contents: &str) -> Result<Vec<Requirement>, String> {
+    let document: toml::Value = toml::from_str(contents).map_err(|e| e.to_string())?;
+    let mut requirements: Vec<Requirement> = document
+        .get("project")
+        .and_then(|project| project.get("dependencies"))
+        .and_then(|dependencies| dependencies.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|dependency| dependency.as_str())
+        .filter_map(parse_requirement)
+        .collect();
+
+    let poetry = document
+        .get("tool")
+        .and_then(|tool| tool.get("poetry"))
+        .and_then(|poetry| poetry.get("dependencies"))
+        .and_then(|dependencies| dependencies.as_table());
+    for (name, value) in poetry.into_iter().flatten() {
+        if name == "python" || value.get("optional").and_then(|v| v.as_bool()) == Some(true) {
+            continue;
+        }
+        let version = value
+            .as_str()
+            .or_else(|| value.get("version").and_then(|version| version.as_str()))
+            .unwrap_or("*");
+        requirements.push(Requirement {
+            name: normalize_name(name),
+            specifier: poetry_to_pep440(version),
+        });
+    }
+    Ok(requirements)
+}
+
+/// Converts Poetry's `^1.2`, `~1.2`, `1.2` and `*` constraints to PEP 440 specifiers.
+fn poetry_to_pep440(constraint: &str) -> String {
+    let constraint = constraint.trim();
+    let bump = |version: &str, index: usize| {
+        let mut parts: Vec<u64> = release(version);
+        parts.truncate(index + 1);
+        parts.resize(index + 1, 0);
+        parts[index] += 1;
+        parts.iter().map(u64::to_string).collect::<Vec<_>>().join(".")
+    };
+    if constraint == "*" || constraint.is_empty() {
+        String::new()
+    } else if let Some(version) = constraint.strip_prefix('^') {
+        // Bump the first non-zero component: ^1.2 -> <2, ^0.3 -> <0.4
+        let parts = release(version);
+        let index = parts.iter().position(|&part| part != 0).unwrap_or(parts.len().saturating_sub(1));
+        format!(">={},<{}", version, bump(version, index))
+    } else if let Some(version) = constraint.strip_prefix('~').filter(|v| !v.starts_with('=')) {
+        let index = if release(version).len() > 1 { 1 } else { 0 };
+        format!(">={},<{}", version, bump(version, index))
+    } else if constraint.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("=={}", constraint)
+    } else {
+        constraint.replace(' ', "")
+    }
+}
+
+/// The numeric release segment of a version, e.g. [1, 26, 0] for "1.26.0rc1".
+fn release(version: &str) -> Vec<u64> {
+    let version = version.split_once('!').map_or(version, |(_, rest)| rest);
+    let mut parts = Vec::new();
+    for part in version.split('.') {
+        let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+        let Ok(number) = digits.parse() else {
+            break;
+        };
+        parts.push(number);
+        // A part like "0rc1" ends the release segment
+        if digits.len() != part.len() {
+            break;
+        }
+    }
+    parts
+}
+
+fn compare_releases(a: &[u64], b: &[u64]) -> Ordering {
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Checks a version against comma-separated PEP 440 clauses like `>=2.31,<3` or `==4.2.*`.
+/// Pre-release and local version tags are ignored.
+fn matches_specifier(version: &str, specifier: &str) -> bool {
+    let installed = release(version);
+    specifier.split(',').map(str::trim).filter(|clause| !clause.is_empty()).all(|clause| {
+        let op_len = clause.find(|c: char| c.is_ascii_alphanumeric()).unwrap_or(clause.len());
+        let (op, value) = clause.split_at(op_len);
+        if op == "===" {
+            return version == value;
+        }
+        let (value, wildcard) = match value.strip_suffix(".*") {
+            Some(prefix) => (prefix, true),
+            None => (value, false),
+        };
+        let wanted = release(value);
+        let prefix_matches = installed.len() >= wanted.len() && installed[..wanted.len()] == wanted[..];
+        let ordering = compare_releases(&installed, &wanted);
+        match op {
+            "==" | "" if wildcard => prefix_matches,
+            "==" | "" => ordering.is_eq(),
+            "!=" if wildcard => !prefix_matches,
+            "!=" => ordering.is_ne(),
+            ">=" => ordering.is_ge(),
+            "<=" => ordering.is_le(),
+            ">" => ordering.is_gt(),
+            "<" => ordering.is_lt(),
+            "~=" => {
+                // ~=1.4.5 means >=1.4.5 and ==1.4.*
+                let prefix = &wanted[..wanted.len().saturating_sub(1)];
+                ordering.is_ge() && installed.len() >= prefix.len() && installed[..prefix.len()] == *prefix
+            }
+            _ => version == value,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validators::ValidationStatus;
+
+    #[test]
+    fn test_parse_requirements() {
+        assert_eq!(
+            parse_requirement("Django==4.2.7"),
+            Some(Requirement { name: "django".to_string(), specifier: "==4.2.7".to_string() })
+        );
+        assert_eq!(parse_requirement("celery[redis] ~= 5.3").unwrap().specifier, "~=5.3");
+        assert_eq!(parse_requirement("Flask_Login (>=0.6)").unwrap().name, "flask-login");
+        assert_eq!(parse_requirement("pkg @ https://example.com/pkg.whl").unwrap().specifier, "");
+        assert_eq!(parse_requirement("pywin32>=306; sys_platform == 'win32'"), None);
+        assert_eq!(parse_requirement("--index-url https://pypi.org/simple"), None);
+
+        let pyproject = r#"
+[project]
+dependencies = ["requests>=2.31,<3", "rich"]
+
+[tool.poetry.dependencies]
+python = "^3.11"
+django = "^4.2"
+numpy = { version = "~1.26", extras = ["all"] }
+black = { version = "*", optional = true }
+"#;
+        let requirements = parse_pyproject(pyproject).unwrap();
+        let specifiers: Vec<(&str, &str)> = requirements
+            .iter()
+            .map(|r| (r.name.as_str(), r.specifier.as_str()))
+            .collect();
+        assert_eq!(
+            specifiers,
+            vec![
+                ("requests", ">=2.31,<3"),
+                ("rich", ""),
+                ("django", ">=4.2,<5"),
+                ("numpy", ">=1.26,<1.27"),
+            ]
+        );
+        assert_eq!(poetry_to_pep440("^0.3.1"), ">=0.3.1,<0.4");
+    }
+
+    #[test]
+    fn test_matches_specifier() {
+        assert!(matches_specifier("4.2.7", "==4.2.7"));
+        assert!(matches_specifier("4.2", "==4.2.0"));
+        assert!(matches_specifier("4.2.7", "==4.2.*"));
+        assert!(!matches_specifier("4.3.0", "==4.2.*"));
+        assert!(matches_specifier("2.31.0", ">=2.31,<3"));
+        assert!(!matches_specifier("3.0.0", ">=2.31,<3"));
+        assert!(matches_specifier("5.3.6", "~=5.3"));
+        assert!(!matches_specifier("6.0", "~=5.3"));
+        assert!(!matches_specifier("5.4.0", "~=5.3.1"));
+        assert!(!matches_specifier("1.0", "!=1.0"));
+        assert!(matches_specifier("2023.11.17", ""));
+        assert_eq!(release("1.26.0rc1"), vec![1, 26, 0]);
+    }
+
+    fn venv_fixture(root: &Path) -> PathBuf {
+        let venv = root.join(".venv");
+        fs::create_dir_all(venv.join("bin")).unwrap();
+        fs::write(venv.join("bin/python"), "").unwrap();
+        fs::write(venv.join("pyvenv.cfg"), "home = /usr/bin\ninclude-system-site-packages = false\nversion = 3.11.4\n").unwrap();
+
+        let site_packages = venv.join("lib/python3.11/site-packages");
+        for (dir, name, version) in [
+            ("Django-4.2.7.dist-info", "Django", "4.2.7"),
+            ("requests-2.30.0.dist-info", "requests", "2.30.0"),
+            ("six-1.16.0.dist-info", "six", "1.16.0"),
+        ] {
+            fs::create_dir_all(site_packages.join(dir)).unwrap();
+            let metadata = format!("Metadata-Version: 2.1\nName: {}\nVersion: {}\n\nDescription", name, version);
+            fs::write(site_packages.join(dir).join("METADATA"), metadata).unwrap();
+        }
+        venv
+    }
+
+    #[test]
+    fn test_venv_and_packages() {
+        let root = tempfile::tempdir().unwrap();
+        let venv = venv_fixture(root.path());
+        fs::write(root.path().join("base.txt"), "six\n").unwrap();
+        let requirements = root.path().join("requirements.txt");
+        fs::write(
+            &requirements,
+            "-r base.txt\nDjango==4.2.7 \\\n    --hash=sha256:abc\nrequests>=2.31  # http\ncelery[redis]~=5.3\n",
+        )
+        .unwrap();
+
+        let check = PythonCheck {
+            venv: Some(venv.display().to_string()),
+            version: Some(">=3.11".to_string()),
+            requirements: vec![requirements.display().to_string()],
+            when: None,
+        };
+        let results = PythonValidator::new(check).check_in(Some(venv.clone()));
+        let messages: Vec<&str> = results.iter().map(|r| r.message.as_str()).collect();
+        let file = requirements.display();
+        assert_eq!(
+            messages,
+            vec![
+                format!("Virtualenv {} is active", venv.display()),
+                format!("{} uses Python 3.11.4 (satisfies >=3.11)", venv.display()),
+                format!("requests 2.30.0 is installed, but {} requires >=2.31", file),
+                format!("Package celery from {} is not installed", file),
+            ]
+        );
+        assert!(matches!(results[2].status, ValidationStatus::Error));
+
+        let check = PythonCheck {
+            venv: Some(venv.display().to_string()),
+            version: Some(">=3.12".to_string()),
+            ..Default::default()
+        };
+        let results = PythonValidator::new(check).check_in(None);
+        assert!(matches!(results[0].status, ValidationStatus::Warning));
+        assert!(matches!(results[1].status, ValidationStatus::Error));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::config::{Backoff, RetryConfig};
+use crate::validators::{ValidationResult, ValidationStatus};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Runs `attempt` until it reports no errors or the retry budget is spent.
+///
+/// The budget is `retries` extra attempts, or, when `wait_until_ready` is set,
+/// as many attempts as fit in that window. The first message of the final
+/// attempt notes how many attempts were made.
+pub fn with_retries<F>(retry: &RetryConfig, mut attempt: F) -> Vec<ValidationResult>
+where
+    F: FnMut() -> Vec<ValidationResult>,
+{
+    let deadline = retry.wait_until_ready.map(|wait| Instant::now() + wait);
+    let mut interval = retry.retry_interval.unwrap_or(DEFAULT_RETRY_INTERVAL);
+    let mut attempts: u32 = 1;
+
+    loop {
+        let mut results = attempt();
+        let failed = results
+            .iter()
+            .any(|r| matches!(r.status, ValidationStatus::Error));
+
+        let delay = match deadline {
+            Some(deadline) => Some(interval.min(deadline.saturating_duration_since(Instant::now())))
+                .filter(|delay| !delay.is_zero()),
+            None => (attempts <= retry.retries).then_some(interval),
+        };
+
+        match delay {
+            Some(delay) if failed => {
+                thread::sleep(delay);
+                attempts += 1;
+                if retry.backoff == Backoff::Exponential {
+                    interval = (interval * 2).min(MAX_RETRY_INTERVAL);
+                }
+            }
+            _ => {
+                if attempts > 1 {
+                    if let Some(first) = results.first_mut() {
+                        first.message = format!("{} (after {} attempts)", first.message, attempts);
+                    }
+                }
+                return results;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flaky(failures: u32) -> impl FnMut() -> Vec<ValidationResult> {
+        let mut calls = 0;
+        move || {
+            calls += 1;
+            if calls <= failures {
+                vec![ValidationResult::error("down", None)]
+            } else {
+                vec![ValidationResult::success("up")]
+            }
+        }
+    }
+
+    #[test]
+    fn test_retries() {
+        let retry = RetryConfig {
+            retries: 2,
+            retry_interval: Some(Duration::from_millis(1)),
+            backoff: Backoff::Exponential,
+            ..Default::default()
+        };
+
+        let results = with_retries(&retry, flaky(0));
+        assert_eq!(results[0].message, "up");
+
+        let results = with_retries(&retry, flaky(2));
+        assert!(matches!(results[0].status, ValidationStatus::Success));
+        assert_eq!(results[0].message, "up (after 3 attempts)");
+
+        let results = with_retries(&retry, flaky(3));
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+        assert_eq!(results[0].message, "down (after 3 attempts)");
+    }
+
+    #[test]
+    fn test_wait_until_ready() {
+        let retry = RetryConfig {
+            retry_interval: Some(Duration::from_millis(5)),
+            wait_until_ready: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+        let results = with_retries(&retry, flaky(4));
+        assert_eq!(results[0].message, "up (after 5 attempts)");
+
+        let retry = RetryConfig {
+            retry_interval: Some(Duration::from_millis(5)),
+            wait_until_ready: Some(Duration::from_millis(30)),
+            ..Default::default()
+        };
+        let started = Instant::now();
+        let results = with_retries(&retry, flaky(u32::MAX));
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,366 @@
+use crate::config::RubyCheck;
+use crate::expand::expand_path;
+use crate::validators::{ValidationResult, Validator};
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// How many missing gems are listed before "and N more".
+const MAX_LISTED: usize = 5;
+
+/// A gem pinned in the GEM section of Gemfile.lock, e.g. `nokogiri (1.16.0-x86_64-linux)`.
+#[derive(Debug, Clone, PartialEq)]
+struct LockedGem {
+    name: String,
+    version: String,
+    platform: Option<String>,
+}
+
+impl LockedGem {
+    /// Whether a matching gemspec (`name-version[-platform].gemspec`) is installed.
+    fn installed_in(&self, specs: &HashSet<String>) -> bool {
+        let stem = format!("{}-{}", self.name, self.version);
+        match &self.platform {
+            Some(platform) => specs.contains(&format!("{}-{}", stem, platform)) || specs.contains(&stem),
+            None => specs.contains(&stem),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Lockfile {
+    gems: Vec<LockedGem>,
+    /// Top-level gem names from the DEPENDENCIES section
+    dependencies: Vec<String>,
+    bundled_with: Option<String>,
+}
+
+pub struct RubyValidator {
+    check: RubyCheck,
+}
+
+impl RubyValidator {
+    pub fn new(check: RubyCheck) -> Self {
+        Self { check }
+    }
+
+    /// Compares Gemfile, Gemfile.lock and the gems installed in `gem_dirs`, like `bundle check`.
+    fn check_in(&self, dir: &Path, gem_dirs: &[PathBuf]) -> Vec<ValidationResult> {
+        let mut results = Vec::new();
+        let Ok(gemfile) = fs::read_to_string(dir.join("Gemfile")) else {
+            results.push(ValidationResult::error(
+                format!("No Gemfile in {}", dir.display()),
+                Some("Set ruby.path to the directory of your Ruby project".to_string()),
+            ));
+            return results;
+        };
+        let Ok(lockfile) = fs::read_to_string(dir.join("Gemfile.lock")) else {
+            results.push(ValidationResult::error(
+                format!("Gemfile.lock is missing in {}", dir.display()),
+                Some("Run `bundle install` and commit Gemfile.lock".to_string()),
+            ));
+            return results;
+        };
+        let lockfile = parse_lockfile(&lockfile);
+
+        let unlocked: Vec<String> = gemfile_gems(&gemfile)
+            .into_iter()
+            .filter(|gem| !lockfile.dependencies.contains(gem))
+            .collect();
+        if !unlocked.is_empty() {
+            results.push(ValidationResult::error(
+                format!("Gemfile.lock is out of date with Gemfile: {} not locked", unlocked.join(", ")),
+                Some("Run `bundle install` and commit Gemfile.lock".to_string()),
+            ));
+        }
+
+        if gem_dirs.is_empty() {
+            results.push(ValidationResult::warning(
+                "Could not find where gems are installed (no BUNDLE_PATH, GEM_HOME or gem command)".to_string(),
+                Some("Install Ruby and run `bundle install`".to_string()),
+            ));
+            return results;
+        }
+        // A lockfile with several platforms lists one spec per platform, e.g.
+        // nokogiri for x86_64-linux and arm64-darwin; any one of them will do
+        let specs = installed_specs(gem_dirs);
+        let mut gems: Vec<(&str, &str)> = Vec::new();
+        let mut missing: Vec<String> = Vec::new();
+        for gem in &lockfile.gems {
+            if gems.contains(&(gem.name.as_str(), gem.version.as_str())) {
+                continue;
+            }
+            gems.push((&gem.name, &gem.version));
+            let installed = lockfile
+                .gems
+                .iter()
+                .filter(|other| other.name == gem.name && other.version == gem.version)
+                .any(|variant| variant.installed_in(&specs));
+            if !installed {
+                missing.push(format!("{} {}", gem.name, gem.version));
+            }
+        }
+
+        if missing.is_empty() {
+            results.push(ValidationResult::success(format!(
+                "All {} gems from Gemfile.lock are installed",
+                gems.len()
+            )));
+        } else {
+            let count = missing.len();
+            let more = match count.saturating_sub(MAX_LISTED) {
+                0 => String::new(),
+                n => format!(" (and {} more)", n),
+            };
+            missing.truncate(MAX_LISTED);
+            results.push(ValidationResult::error(
+                format!(
+                    "{} of {} gems from Gemfile.lock are not installed: {}{}",
+                    count,
+                    gems.len(),
+                    missing.join(", "),
+                    more
+                ),
+                Some("Run `bundle install`".to_string()),
+            ));
+        }
+
+        if let Some(expected) = &lockfile.bundled_with {
+            match bundler_version(dir) {
+                Some(version) if &version != expected => results.push(ValidationResult::warning(
+                    format!("Bundler {} is installed, but Gemfile.lock was bundled with {}", version, expected),
+                    Some(format!("Run `gem install bundler:{}`", expected)),
+                )),
+                Some(_) => {}
+                None => results.push(ValidationResult::error(
+                    "bundle not found".to_string(),
+                    Some(format!("Run `gem install bundler:{}`", expected)),
+                )),
+            }
+        }
+
+        results
+    }
+}
+
+impl Validator for RubyValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        let dir = match expand_path(self.check.path.as_deref().unwrap_or(".")) {
+            Ok(dir) => PathBuf::from(dir),
+            Err(e) => {
+                return Ok(vec![ValidationResult::error(
+                    format!("Invalid ruby path in config: {}", e),
+                    Some(e.suggestion()),
+                )])
+            }
+        };
+        let gem_dirs = gem_dirs(&dir);
+        Ok(self.check_in(&dir, &gem_dirs))
+    }
+}
+
+fn parse_lockfile(contents: &str) -> Lockfile {
+    let mut lockfile = Lockfile::default();
+    let mut section = "";
+    for line in contents.lines() {
+        if !line.starts_with(' ') {
+            section = line.trim();
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let line = line.trim();
+        match section {
+            // Specs are indented by four spaces, their own dependencies by six
+            "GEM" if indent == 4 => {
+                let Some((name, version)) = line.split_once(" (") else {
+                    continue;
+                };
+                let version = version.trim_end_matches(')');
+                // Gem versions never contain '-', so anything after one is the platform
+                let (version, platform) = match version.split_once('-') {
+                    Some((version, platform)) => (version, Some(platform.to_string())),
+                    None => (version, None),
+                };
+                lockfile.gems.push(LockedGem {
+                    name: name.to_string(),
+                    version: version.to_string(),
+                    platform,
+                });
+            }
+            "DEPENDENCIES" if indent == 2 => {
+                let name = line.split_whitespace().next().unwrap_or_default();
+                lockfile.dependencies.push(name.trim_end_matches('!').to_string());
+            }
+            "BUNDLED WITH" => lockfile.bundled_with = Some(line.to_string()),
+            _ => {}
+        }
+    }
+    lockfile
+}
+
+/// Gem names declared with `gem "name"` in a Gemfile.
+fn gemfile_gems(contents: &str) -> Vec<String> {
+    let re = Regex::new(r#"(?m)^\s*gem\s*\(?\s*["']([^"']+)["']"#).expect("valid regex");
+    let mut gems: Vec<String> = re.captures_iter(contents).map(|c| c[1].to_string()).collect();
+    gems.dedup();
+    gems
+}
+
+/// Directories that may hold installed gems: BUNDLE_PATH, then GEM_HOME and GEM_PATH,
+/// then the gem paths reported by RubyGems.
+fn gem_dirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    let bundle_path = env::var("BUNDLE_PATH").ok().map(PathBuf::from).or_else(|| {
+        let home_config = expand_path("~/.bundle/config").ok().map(PathBuf::from);
+        [Some(dir.join(".bundle/config")), home_config]
+            .into_iter()
+            .flatten()
+            .find_map(|config| bundle_config_path(&fs::read_to_string(config).ok()?))
+            .map(|path| dir.join(path))
+    });
+    if let Some(path) = bundle_path {
+        // Bundler installs into <path>/ruby/<abi version>
+        let pattern = path.join("ruby/*").display().to_string();
+        dirs.extend(glob::glob(&pattern).into_iter().flatten().flatten());
+        dirs.push(path);
+    }
+
+    for var in ["GEM_HOME", "GEM_PATH"] {
+        if let Ok(value) = env::var(var) {
+            dirs.extend(env::split_paths(&value));
+        }
+    }
+
+    if let Ok(gem) = which::which("gem") {
+        if let Ok(output) = Command::new(gem).args(["env", "gempath"]).current_dir(dir).output() {
+            if output.status.success() {
+                dirs.extend(env::split_paths(String::from_utf8_lossy(&output.stdout).trim()));
+            }
+        }
+    }
+
+    dirs.retain(|dir| dir.join("specifications").is_dir());
+    dirs.dedup();
+    dirs
+}
+
+/// Reads `BUNDLE_PATH: "vendor/bundle"` from a .bundle/config file.
+fn bundle_config_path(config: &str) -> Option<String> {
+    config.lines().find_map(|line| {
+        let value = line.strip_prefix("BUNDLE_PATH:")?;
+        Some(value.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+    })
+}
+
+/// Names of installed gemspecs without the extension, e.g. `rack-3.0.8`.
+fn installed_specs(gem_dirs: &[PathBuf]) -> HashSet<String> {
+    gem_dirs
+        .iter()
+        .flat_map(|dir| [dir.join("specifications"), dir.join("specifications/default")])
+        .filter_map(|specs| fs::read_dir(specs).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            name.strip_suffix(".gemspec").map(str::to_string)
+        })
+        .collect()
+}
+
+/// Runs `bundle --version`, which prints "Bundler version 2.4.22" or just "2.5.3".
+fn bundler_version(dir: &Path) -> Option<String> {
+    let program = which::which("bundle").ok()?;
+    let output = Command::new(program).arg("--version").current_dir(dir).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.split_whitespace().last().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validators::ValidationStatus;
+
+    const LOCKFILE: &str = "\
+GIT
+  remote: https://github.com/example/widgets.git
+  revision: 0123abcd
+  specs:
+    widgets (0.1.0)
+
+GEM
+  remote: https://rubygems.org/
+  specs:
+    nokogiri (1.16.0-arm64-darwin)
+      racc (~> 1.4)
+    nokogiri (1.16.0-x86_64-linux)
+      racc (~> 1.4)
+    racc (1.7.3)
+    rack (3.0.8)
+
+PLATFORMS
+  arm64-darwin
+  x86_64-linux
+
+DEPENDENCIES
+  nokogiri
+  rack (~> 3.0)
+  widgets!
+
+BUNDLED WITH
+   2.4.22
+";
+
+    #[test]
+    fn test_parse_lockfile() {
+        let lockfile = parse_lockfile(LOCKFILE);
+        assert_eq!(lockfile.gems.len(), 4);
+        assert_eq!(
+            lockfile.gems[1],
+            LockedGem {
+                name: "nokogiri".to_string(),
+                version: "1.16.0".to_string(),
+                platform: Some("x86_64-linux".to_string()),
+            }
+        );
+        assert_eq!(lockfile.dependencies, vec!["nokogiri", "rack", "widgets"]);
+        assert_eq!(lockfile.bundled_with.as_deref(), Some("2.4.22"));
+
+        let gemfile = "source 'https://rubygems.org'\ngem \"rack\", \"~> 3.0\"\n  gem 'nokogiri'\n# gem 'puma'\ngem 'pg'\n";
+        assert_eq!(gemfile_gems(gemfile), vec!["rack", "nokogiri", "pg"]);
+        assert_eq!(bundle_config_path("---\nBUNDLE_PATH: \"vendor/bundle\"\n").as_deref(), Some("vendor/bundle"));
+    }
+
+    #[test]
+    fn test_installed_gems() {
+        let project = tempfile::tempdir().unwrap();
+        fs::write(project.path().join("Gemfile"), "gem 'nokogiri'\ngem 'rack'\ngem 'pg'\n").unwrap();
+        let lockfile = LOCKFILE.replace("BUNDLED WITH\n   2.4.22\n", "");
+        fs::write(project.path().join("Gemfile.lock"), lockfile).unwrap();
+        let gems = tempfile::tempdir().unwrap();
+        let specs = gems.path().join("specifications");
+        fs::create_dir(&specs).unwrap();
+        fs::write(specs.join("nokogiri-1.16.0-x86_64-linux.gemspec"), "").unwrap();
+        fs::write(specs.join("rack-3.0.8.gemspec"), "").unwrap();
+
+        let validator = RubyValidator::new(RubyCheck::default());
+        let results = validator.check_in(project.path(), &[gems.path().to_path_buf()]);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].message, "Gemfile.lock is out of date with Gemfile: pg not locked");
+        assert!(matches!(results[1].status, ValidationStatus::Error));
+        assert_eq!(results[1].message, "1 of 3 gems from Gemfile.lock are not installed: racc 1.7.3");
+
+        fs::write(specs.join("racc-1.7.3.gemspec"), "").unwrap();
+        let results = validator.check_in(project.path(), &[gems.path().to_path_buf()]);
+        assert_eq!(results[1].message, "All 3 gems from Gemfile.lock are installed");
+
+        let results = validator.check_in(project.path(), &[]);
+        assert!(matches!(results[1].status, ValidationStatus::Warning));
+    }
+}
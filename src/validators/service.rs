@@ -0,0 +1,193 @@
+use crate::config::ServiceCheck;
+use crate::validators::{ValidationResult, Validator};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+const SYSTEMD_RUNTIME_DIR: &str = "/run/systemd/system";
+const PROPERTIES: &str = "LoadState,ActiveState,SubState,UnitFileState";
+
+pub struct ServiceValidator {
+    check: ServiceCheck,
+}
+
+impl ServiceValidator {
+    pub fn new(check: ServiceCheck) -> Self {
+        Self { check }
+    }
+
+    fn unit(&self) -> String {
+        if self.check.name.contains('.') {
+            self.check.name.clone()
+        } else {
+            format!("{}.service", self.check.name)
+        }
+    }
+
+    /// The `systemctl` invocation for this unit, e.g. `sudo systemctl start docker.service`.
+    fn systemctl(&self, action: &str) -> String {
+        if self.check.user {
+            format!("systemctl --user {} {}", action, self.unit())
+        } else {
+            format!("sudo systemctl {} {}", action, self.unit())
+        }
+    }
+
+    fn journalctl(&self) -> String {
+        let scope = if self.check.user { "--user " } else { "" };
+        format!("journalctl {}-u {} -e", scope, self.unit())
+    }
+
+    /// Turns the unit properties from `systemctl show` into results.
+    fn check_state(&self, properties: &HashMap<String, String>) -> Vec<ValidationResult> {
+        let unit = self.unit();
+        let get = |key: &str| properties.get(key).map(String::as_str).unwrap_or_default();
+        let mut results = Vec::new();
+
+        if get("LoadState") == "not-found" {
+            let hint = if self.check.user {
+                "Install the unit file in ~/.config/systemd/user/ and run `systemctl --user daemon-reload`".to_string()
+            } else {
+                format!("Install the package that provides {}", unit)
+            };
+            results.push(ValidationResult::error(format!("{} not found", unit), Some(hint)));
+            return results;
+        }
+
+        let state = format!("{} ({})", get("ActiveState"), get("SubState"));
+        match get("ActiveState") {
+            "active" | "reloading" => results.push(ValidationResult::success(format!("{} is {}", unit, state))),
+            "activating" => results.push(ValidationResult::warning(
+                format!("{} is {}", unit, state),
+                Some(format!("Wait for it to finish starting or check `{}`", self.journalctl())),
+            )),
+            "failed" => results.push(ValidationResult::error(
+                format!("{} has failed", unit),
+                Some(format!(
+                    "Check the logs with `{}`, then run `{}`",
+                    self.journalctl(),
+                    self.systemctl("restart")
+                )),
+            )),
+            _ => {
+                let start = if get("UnitFileState") == "disabled" && self.check.enabled {
+                    self.systemctl("enable --now")
+                } else {
+                    self.systemctl("start")
+                };
+                results.push(ValidationResult::error(
+                    format!("{} is {}", unit, state),
+                    Some(format!("Start it with `{}`", start)),
+                ));
+            }
+        }
+
+        if self.check.enabled {
+            match get("UnitFileState") {
+                "enabled" | "enabled-runtime" | "static" | "alias" | "indirect" | "generated" => {
+                    results.push(ValidationResult::success(format!("{} is enabled", unit)))
+                }
+                state => results.push(ValidationResult::error(
+                    format!("{} is not enabled ({})", unit, state),
+                    Some(format!("Enable it with `{}`", self.systemctl("enable"))),
+                )),
+            }
+        }
+
+        results
+    }
+}
+
+impl Validator for ServiceValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        let unit = self.unit();
+        if !Path::new(SYSTEMD_RUNTIME_DIR).is_dir() {
+            return Ok(vec![ValidationResult::warning(
+                format!("Cannot check {}: systemd is not running", unit),
+                Some("Service checks need systemd; containers and WSL often run without it".to_string()),
+            )]);
+        }
+
+        let mut command = Command::new("systemctl");
+        if self.check.user {
+            command.arg("--user");
+        }
+        let output = match command.args(["show", "--property", PROPERTIES, &unit]).output() {
+            Ok(output) => output,
+            Err(e) => {
+                return Ok(vec![ValidationResult::warning(
+                    format!("Cannot check {}: systemctl not found", unit),
+                    Some(format!("Error: {}", e)),
+                )])
+            }
+        };
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let hint = if self.check.user {
+                "Is a user session running? `systemctl --user` needs XDG_RUNTIME_DIR and a login session".to_string()
+            } else {
+                format!("systemctl failed: {}", stderr.trim())
+            };
+            return Ok(vec![ValidationResult::warning(
+                format!("Cannot read the state of {}", unit),
+                Some(hint),
+            )]);
+        }
+
+        Ok(self.check_state(&parse_properties(&String::from_utf8_lossy(&output.stdout))))
+    }
+}
+
+/// Parses `Key=value` lines from `systemctl show`.
+fn parse_properties(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validators::ValidationStatus;
+
+    fn check(name: &str, user: bool, enabled: bool) -> ServiceCheck {
+        ServiceCheck {
+            name: name.to_string(),
+            user,
+            enabled,
+            when: None,
+        }
+    }
+
+    #[test]
+    fn test_service_states() {
+        let running = parse_properties("LoadState=loaded\nActiveState=active\nSubState=running\nUnitFileState=enabled\n");
+        let results = ServiceValidator::new(check("docker", false, true)).check_state(&running);
+        assert!(results.iter().all(|r| matches!(r.status, ValidationStatus::Success)));
+        assert_eq!(results[0].message, "docker.service is active (running)");
+
+        let stopped = parse_properties("LoadState=loaded\nActiveState=inactive\nSubState=dead\nUnitFileState=disabled\n");
+        let results = ServiceValidator::new(check("docker", false, true)).check_state(&stopped);
+        assert_eq!(results[0].message, "docker.service is inactive (dead)");
+        assert_eq!(
+            results[0].suggestion.as_deref(),
+            Some("Start it with `sudo systemctl enable --now docker.service`")
+        );
+        assert_eq!(results[1].message, "docker.service is not enabled (disabled)");
+
+        let failed = parse_properties("LoadState=loaded\nActiveState=failed\nSubState=failed\n");
+        let results = ServiceValidator::new(check("syncthing.service", true, false)).check_state(&failed);
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].suggestion.as_deref(),
+            Some("Check the logs with `journalctl --user -u syncthing.service -e`, then run `systemctl --user restart syncthing.service`")
+        );
+
+        let missing = parse_properties("LoadState=not-found\nActiveState=inactive\nSubState=dead\n");
+        let results = ServiceValidator::new(check("redis", false, false)).check_state(&missing);
+        assert_eq!(results[0].message, "redis.service not found");
+    }
+}
@@ -0,0 +1,125 @@
+use crate::config::SysctlCheck;
+use crate::validators::{ValidationResult, Validator};
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PROC_SYS: &str = "/proc/sys";
+
+pub struct SysctlValidator {
+    check: SysctlCheck,
+}
+
+impl SysctlValidator {
+    pub fn new(check: SysctlCheck) -> Self {
+        Self { check }
+    }
+
+    /// Maps `vm.max_map_count` to `<root>/vm/max_map_count`, rejecting names
+    /// that would escape the root.
+    fn path_in(&self, root: &Path) -> Option<PathBuf> {
+        let name = &self.check.name;
+        let separator = if name.contains('/') { '/' } else { '.' };
+        let parts: Vec<&str> = name.split(separator).collect();
+        if parts.iter().any(|part| part.is_empty() || *part == "..") {
+            return None;
+        }
+        Some(parts.iter().fold(root.to_path_buf(), |path, part| path.join(part)))
+    }
+
+    fn check_in(&self, root: &Path) -> ValidationResult {
+        let name = &self.check.name;
+        let Some(path) = self.path_in(root) else {
+            return ValidationResult::error(
+                format!("Invalid sysctl name '{}' in config", name),
+                Some("Use a dotted name like vm.max_map_count".to_string()),
+            );
+        };
+
+        let raw = match fs::read_to_string(&path) {
+            Ok(raw) => raw.trim().to_string(),
+            Err(e) => {
+                let hint = if cfg!(target_os = "linux") {
+                    format!("Check the name or load the kernel module that provides it (Error: {})", e)
+                } else {
+                    format!("sysctl checks read {} and only work on Linux", PROC_SYS)
+                };
+                return ValidationResult::warning(format!("sysctl {} not found", name), Some(hint));
+            }
+        };
+
+        if self.check.min.is_none() && self.check.max.is_none() {
+            return ValidationResult::success(format!("{} is {}", name, raw));
+        }
+        let Ok(value) = raw.parse::<u64>() else {
+            return ValidationResult::warning(
+                format!("{} is '{}', which is not a number", name, raw),
+                Some("Remove min/max for multi-value tunables".to_string()),
+            );
+        };
+
+        let (bound, expected) = match (self.check.min, self.check.max) {
+            (Some(min), _) if value < min => ("at least", min),
+            (_, Some(max)) if value > max => ("at most", max),
+            _ => return ValidationResult::success(format!("{} is {}", name, value)),
+        };
+        ValidationResult::error(
+            format!("{} is {}, need {} {}", name, value, bound, expected),
+            Some(format!(
+                "Run `sudo sysctl -w {}={}` and add `{} = {}` to a file in /etc/sysctl.d/ to keep it after a reboot",
+                name, expected, name, expected
+            )),
+        )
+    }
+}
+
+impl Validator for SysctlValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        Ok(vec![self.check_in(Path::new(PROC_SYS))])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validators::ValidationStatus;
+
+    fn check(name: &str, min: Option<u64>, max: Option<u64>) -> SysctlCheck {
+        SysctlCheck {
+            name: name.to_string(),
+            min,
+            max,
+            when: None,
+        }
+    }
+
+    #[test]
+    fn test_sysctl_thresholds() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("vm")).unwrap();
+        fs::write(root.path().join("vm/max_map_count"), "65530\n").unwrap();
+        fs::create_dir_all(root.path().join("net/ipv4")).unwrap();
+        fs::write(root.path().join("net/ipv4/ip_local_port_range"), "32768\t60999\n").unwrap();
+
+        let result = SysctlValidator::new(check("vm.max_map_count", Some(1024), None)).check_in(root.path());
+        assert!(matches!(result.status, ValidationStatus::Success));
+        assert_eq!(result.message, "vm.max_map_count is 65530");
+
+        let result = SysctlValidator::new(check("vm.max_map_count", Some(262144), None)).check_in(root.path());
+        assert!(matches!(result.status, ValidationStatus::Error));
+        assert_eq!(result.message, "vm.max_map_count is 65530, need at least 262144");
+        assert!(result.suggestion.unwrap().starts_with("Run `sudo sysctl -w vm.max_map_count=262144`"));
+
+        let result = SysctlValidator::new(check("vm/max_map_count", None, Some(1000))).check_in(root.path());
+        assert_eq!(result.message, "vm/max_map_count is 65530, need at most 1000");
+
+        let result = SysctlValidator::new(check("net.ipv4.ip_local_port_range", None, None)).check_in(root.path());
+        assert_eq!(result.message, "net.ipv4.ip_local_port_range is 32768\t60999");
+
+        let result = SysctlValidator::new(check("fs.inotify.max_user_watches", Some(1), None)).check_in(root.path());
+        assert!(matches!(result.status, ValidationStatus::Warning));
+
+        let result = SysctlValidator::new(check("vm...passwd", None, None)).check_in(root.path());
+        assert!(matches!(result.status, ValidationStatus::Error));
+    }
+}
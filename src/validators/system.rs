@@ -0,0 +1,239 @@
+use crate::config::SystemCheck;
+use crate::expand::expand_path;
+use crate::units::format_size;
+use crate::validators::{ValidationResult, Validator};
+use anyhow::Result;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::thread;
+
+const MEMINFO_PATH: &str = "/proc/meminfo";
+/// MemTotal excludes what the kernel and firmware reserve, so an 8 GiB machine reports
+/// a little less; totals within this percentage of `min_memory` still pass.
+const MEMORY_TOLERANCE_PERCENT: u64 = 10;
+
+pub struct SystemValidator {
+    check: SystemCheck,
+}
+
+impl SystemValidator {
+    pub fn new(check: SystemCheck) -> Self {
+        Self { check }
+    }
+
+    fn check_disk(&self, min_free: u64, results: &mut Vec<ValidationResult>) {
+        let path = match expand_path(self.check.path.as_deref().unwrap_or(".")) {
+            Ok(path) => path,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Invalid system path in config: {}", e),
+                    Some(e.suggestion()),
+                ));
+                return;
+            }
+        };
+
+        match free_disk_space(Path::new(&path)) {
+            Ok(free) if free >= min_free => results.push(ValidationResult::success(format!(
+                "{} free disk space on {} (need {})",
+                format_size(free),
+                path,
+                format_size(min_free)
+            ))),
+            Ok(free) => results.push(ValidationResult::error(
+                format!(
+                    "Only {} free disk space on {}, need {}",
+                    format_size(free),
+                    path,
+                    format_size(min_free)
+                ),
+                Some(format!(
+                    "Free up {} on {} (e.g. remove unused Docker images with `docker system prune`)",
+                    format_size(min_free - free),
+                    path
+                )),
+            )),
+            Err(e) => results.push(ValidationResult::warning(
+                format!("Could not determine free disk space on {}", path),
+                Some(format!("Error: {}", e)),
+            )),
+        }
+    }
+
+    fn check_memory(&self, min_memory: u64, results: &mut Vec<ValidationResult>) {
+        let total = fs::read_to_string(MEMINFO_PATH)
+            .ok()
+            .and_then(|meminfo| parse_meminfo(&meminfo, "MemTotal"));
+        results.push(memory_result(total, min_memory));
+    }
+
+    fn check_cpus(&self, min_cpus: usize, results: &mut Vec<ValidationResult>) {
+        match thread::available_parallelism() {
+            Ok(cpus) if cpus.get() >= min_cpus => results.push(ValidationResult::success(format!(
+                "{} CPU(s) available (need {})",
+                cpus, min_cpus
+            ))),
+            Ok(cpus) => results.push(ValidationResult::error(
+                format!("Only {} CPU(s) available, need {}", cpus, min_cpus),
+                Some("Use a machine with more cores or raise the VM/container CPU limit".to_string()),
+            )),
+            Err(e) => results.push(ValidationResult::warning(
+                "Could not determine the number of CPUs".to_string(),
+                Some(format!("Error: {}", e)),
+            )),
+        }
+    }
+}
+
+impl Validator for SystemValidator {
+    fn validate(&self) -> Result<Vec<ValidationResult>> {
+        let mut results = Vec::new();
+
+        if let Some(min_free) = self.check.min_free_disk {
+            self.check_disk(min_free, &mut results);
+        }
+        if let Some(min_memory) = self.check.min_memory {
+            self.check_memory(min_memory, &mut results);
+        }
+        if let Some(min_cpus) = self.check.min_cpus {
+            self.check_cpus(min_cpus, &mut results);
+        }
+
+        Ok(results)
+    }
+}
+
+/// Reads a field such as "MemTotal:  16318748 kB" from /proc/meminfo, in bytes.
+fn parse_meminfo(meminfo: &str, field: &str) -> Option<u64> {
+    meminfo.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if name.trim() != field {
+            return None;
+        }
+        let mut parts = value.split_whitespace();
+        let amount: u64 = parts.next()?.parse().ok()?;
+        match parts.next() {
+            Some("kB") => Some(amount * 1024),
+            None => Some(amount),
+            Some(_) => None,
+        }
+    })
+}
+
+/// Returns the space available to unprivileged users on the filesystem holding `path`.
+#[cfg(unix)]
+fn free_disk_space(path: &Path) -> io::Result<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    // SAFETY: statvfs only writes into the zeroed struct we pass it
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // The field types differ between platforms
+    #[allow(clippy::unnecessary_cast)]
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+fn free_disk_space(_path: &Path) -> io::Result<u64> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "free disk space is only checked on Unix",
+    ))
+}
+
+fn memory_result(total: Option<u64>, min_memory: u64) -> ValidationResult {
+    match total {
+        Some(total) if has_enough_memory(total, min_memory) => {
+            let slack = match total < min_memory {
+                true => format!(", within the {}% allowed for kernel reservations", MEMORY_TOLERANCE_PERCENT),
+                false => String::new(),
+            };
+            ValidationResult::success(format!(
+                "{} of memory (need {}{})",
+                format_size(total),
+                format_size(min_memory),
+                slack
+            ))
+        }
+        Some(total) => ValidationResult::error(
+            format!(
+                "Only {} of memory, need {}",
+                format_size(total),
+                format_size(min_memory)
+            ),
+            Some("Use a machine with more memory or raise the VM memory limit".to_string()),
+        ),
+        None => ValidationResult::warning(
+            "Could not determine total memory".to_string(),
+            Some(format!("{} is not available on this platform", MEMINFO_PATH)),
+        ),
+    }
+}
+
+fn has_enough_memory(total: u64, min_memory: u64) -> bool {
+    let tolerance = min_memory / 100 * MEMORY_TOLERANCE_PERCENT;
+    total >= min_memory.saturating_sub(tolerance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validators::ValidationStatus;
+
+    #[test]
+    fn test_parse_meminfo() {
+        let meminfo = "MemTotal:       16318748 kB\nMemFree:         1017048 kB\nHugePages_Total:       0\n";
+        assert_eq!(parse_meminfo(meminfo, "MemTotal"), Some(16_318_748 * 1024));
+        assert_eq!(parse_meminfo(meminfo, "HugePages_Total"), Some(0));
+        assert_eq!(parse_meminfo(meminfo, "MemAvailable"), None);
+    }
+
+    #[test]
+    fn test_memory_tolerance() {
+        // MemTotal of an 8 GiB laptop with memory reserved for integrated graphics
+        assert!(has_enough_memory(7_918_116 * 1024, 8 << 30));
+        assert!(!has_enough_memory(6 << 30, 8 << 30));
+        assert!(has_enough_memory(1, 0));
+
+        assert_eq!(
+            memory_result(Some(7_918_116 * 1024), 8 << 30).message,
+            format!(
+                "{} of memory (need 8.0 GiB, within the 10% allowed for kernel reservations)",
+                format_size(7_918_116 * 1024)
+            )
+        );
+        assert_eq!(memory_result(Some(16 << 30), 8 << 30).message, "16.0 GiB of memory (need 8.0 GiB)");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_disk_and_cpu_thresholds() {
+        let check = SystemCheck {
+            min_free_disk: Some(1),
+            min_cpus: Some(1),
+            ..Default::default()
+        };
+        let results = SystemValidator::new(check).validate().unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| matches!(r.status, ValidationStatus::Success)));
+
+        let check = SystemCheck {
+            min_free_disk: Some(u64::MAX),
+            path: Some("/".to_string()),
+            min_cpus: Some(100_000),
+            ..Default::default()
+        };
+        let results = SystemValidator::new(check).validate().unwrap();
+        assert!(matches!(results[0].status, ValidationStatus::Error));
+        assert!(results[0].message.starts_with("Only "));
+        let need = format!(" free disk space on /, need {}", format_size(u64::MAX));
+        assert!(results[0].message.ends_with(&need));
+        assert!(results[1].message.ends_with(" CPU(s) available, need 100000"));
+    }
+}
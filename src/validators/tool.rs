@@ -1,4 +1,5 @@
 use crate::config::ToolCheck;
+use crate::expand::expand_path;
 use crate::validators::{ValidationResult, Validator};
 use anyhow::Result;
 use std::process::Command;
@@ -66,44 +67,50 @@ impl ToolValidator {
     }
 
     fn check_version_requirement(&self, version: &str, requirement: &str) -> bool {
-        // Parse the detected version
-        let ver = match semver::Version::parse(version) {
-            Ok(v) => v,
-            Err(_) => {
-                // If version parsing fails, try to clean it or fallback to string matching
-                // Some tools might return versions like "1.2" which semver requires "1.2.0"
-                let parts: Vec<&str> = version.split('.').collect();
-                if parts.len() == 2 {
-                    let cleaned = format!("{}.0", version);
-                    if let Ok(v) = semver::Version::parse(&cleaned) {
-                        v
-                    } else {
-                        return version.contains(requirement);
-                    }
-                } else if parts.len() == 1 {
-                    let cleaned = format!("{}.0.0", version);
-                    if let Ok(v) = semver::Version::parse(&cleaned) {
-                        v
-                    } else {
-                        return version.contains(requirement);
-                    }
+        check_version_requirement(version, requirement)
+    }
+}
+
+/// Checks a detected version against a semver requirement, padding short
+/// versions like "1.2" to "1.2.0" and falling back to substring matching.
+pub(crate) fn check_version_requirement(version: &str, requirement: &str) -> bool {
+    // Parse the detected version
+    let ver = match semver::Version::parse(version) {
+        Ok(v) => v,
+        Err(_) => {
+            // If version parsing fails, try to clean it or fallback to string matching
+            // Some tools might return versions like "1.2" which semver requires "1.2.0"
+            let parts: Vec<&str> = version.split('.').collect();
+            if parts.len() == 2 {
+                let cleaned = format!("{}.0", version);
+                if let Ok(v) = semver::Version::parse(&cleaned) {
+                    v
                 } else {
                     return version.contains(requirement);
                 }
+            } else if parts.len() == 1 {
+                let cleaned = format!("{}.0.0", version);
+                if let Ok(v) = semver::Version::parse(&cleaned) {
+                    v
+                } else {
+                    return version.contains(requirement);
+                }
+            } else {
+                return version.contains(requirement);
             }
-        };
+        }
+    };
 
-        // Parse the requirement
-        // semver crate expects requirements like ">=1.2.3" or "1.2.3"
-        // If it starts with =, remove it as VersionReq might not like it (or it might be fine)
-        let cleaned_req = requirement.trim_start_matches('=');
-        
-        match semver::VersionReq::parse(cleaned_req) {
-            Ok(req) => req.matches(&ver),
-            Err(_) => {
-                // Fallback to simple string comparison if requirement is not valid semver req
-                version.contains(requirement)
-            }
+    // Parse the requirement
+    // semver crate expects requirements like ">=1.2.3" or "1.2.3"
+    // If it starts with =, remove it as VersionReq might not like it (or it might be fine)
+    let cleaned_req = requirement.trim_start_matches('=');
+    
+    match semver::VersionReq::parse(cleaned_req) {
+        Ok(req) => req.matches(&ver),
+        Err(_) => {
+            // Fallback to simple string comparison if requirement is not valid semver req
+            version.contains(requirement)
         }
     }
 }
@@ -112,23 +119,40 @@ impl Validator for ToolValidator {
     fn validate(&self) -> Result<Vec<ValidationResult>> {
         let mut results = Vec::new();
 
+        // An explicit path replaces the PATH lookup
+        let tool_path = match self.check.path.as_deref().map(expand_path).transpose() {
+            Ok(tool_path) => tool_path,
+            Err(e) => {
+                results.push(ValidationResult::error(
+                    format!("Invalid path for {} in config: {}", self.check.name, e),
+                    Some(e.suggestion()),
+                ));
+                return Ok(results);
+            }
+        };
+
         // Determine binaries to check
-        let binaries = match self.check.name.as_str() {
-            "python" => vec!["python3", "python"],
-            name => vec![name],
+        let binaries = match (&tool_path, self.check.name.as_str()) {
+            (Some(tool_path), _) => vec![tool_path.as_str()],
+            (None, "python") => vec!["python3", "python"],
+            (None, name) => vec![name],
         };
 
         let bin_found = binaries.iter().any(|b| which::which(b).is_ok());
 
         if !bin_found {
+            let location = tool_path
+                .as_ref()
+                .map(|tool_path| format!(" at {}", tool_path))
+                .unwrap_or_default();
             if self.check.required {
                 results.push(ValidationResult::error(
-                    format!("{} not found", self.check.name),
+                    format!("{} not found{}", self.check.name, location),
                     Some(format!("Install {} to continue", self.check.name)),
                 ));
             } else {
                 results.push(ValidationResult::warning(
-                    format!("{} not found (optional)", self.check.name),
+                    format!("{} not found{} (optional)", self.check.name, location),
                     None,
                 ));
             }
@@ -137,16 +161,19 @@ impl Validator for ToolValidator {
 
         // If version check is required
         if let Some(version_req) = &self.check.version {
-            let tool_names = match self.check.name.as_str() {
-                "python" => vec!["python3", "python"],
-                name => vec![name],
-            };
-
             let mut last_error = None;
             let mut detected_version = None;
 
-            for tool_name in tool_names {
-                if let Some((cmd, args)) = self.get_version_command(tool_name) {
+            for tool_name in binaries {
+                let version_command = match &tool_path {
+                    // Pick the version arguments by tool name, but run the configured binary
+                    Some(tool_path) => self
+                        .get_version_command(&self.check.name)
+                        .map(|(_, args)| (tool_path.as_str(), args)),
+                    None => self.get_version_command(tool_name),
+                };
+
+                if let Some((cmd, args)) = version_command {
                     // Check if binary exists before running
                     if which::which(cmd).is_err() {
                         continue;
@@ -208,8 +235,10 @@ mod tests {
     fn test_parse_version() {
         let check = ToolCheck {
             name: "test".to_string(),
+            path: None,
             version: None,
             required: true,
+            when: None,
         };
         let validator = ToolValidator::new(check);
 
@@ -239,8 +268,10 @@ OpenJDK 64-Bit Server VM Temurin-25.0.1+8 (build 25.0.1+8-LTS, mixed mode, shari
     fn test_check_version_requirement() {
         let check = ToolCheck {
             name: "test".to_string(),
+            path: None,
             version: None,
             required: true,
+            when: None,
         };
         let validator = ToolValidator::new(check);
 
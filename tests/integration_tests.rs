@@ -161,3 +161,98 @@ files:
         .success()
         .stdout(predicate::str::contains(format!("Directory {} exists", dir_path)));
 }
+
+#[test]
+fn test_cli_unresolved_variables() {
+    let mut file = NamedTempFile::new().unwrap();
+    writeln!(
+        file,
+        r#"version: "1"
+tools:
+  - name: mytool
+    path: "${{ENVCHECK_TOOL_DIR:-/nonexistent}}/mytool"
+network:
+  - url: "http://${{ENVCHECK_API_HOST}}/health"
+"#
+    ).unwrap();
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_envcheck"));
+    cmd.arg("--config").arg(file.path())
+       .env_remove("ENVCHECK_TOOL_DIR")
+       .env_remove("ENVCHECK_API_HOST");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("mytool not found at /nonexistent/mytool"))
+        .stdout(predicate::str::contains("Invalid URL 'http://${ENVCHECK_API_HOST}/health' in config: unresolved variable ${ENVCHECK_API_HOST}"));
+}
+
+#[test]
+fn test_cli_offline_skips_network() {
+    let mut file = NamedTempFile::new().unwrap();
+    writeln!(
+        file,
+        r#"version: "1"
+network:
+  - url: http://127.0.0.1:1/health
+  - dns: api.myapp.test
+databases:
+  - url: postgres://app@db.myapp.test:5432/app
+docker:
+  host: tcp://docker.myapp.test:2376
+"#
+    ).unwrap();
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_envcheck"));
+    cmd.arg("--config").arg(file.path()).arg("--offline").arg("--json");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped http://127.0.0.1:1/health (offline)"))
+        .stdout(predicate::str::contains("Skipped PostgreSQL at db.myapp.test:5432 (offline)"))
+        .stdout(predicate::str::contains("Skipped tcp://docker.myapp.test:2376 (offline)"))
+        .stdout(predicate::str::contains("\"skipped\": 4"));
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_cli_when_condition() {
+    let mut file = NamedTempFile::new().unwrap();
+    writeln!(
+        file,
+        r#"version: "1"
+tools:
+  - name: envcheck-windows-only-tool
+    when: {{ os: windows }}
+network:
+  - url: http://127.0.0.1:1/windows-only
+    when: {{ os: windows }}
+"#
+    ).unwrap();
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_envcheck"));
+    cmd.arg("--config").arg(file.path()).arg("--offline");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped envcheck-windows-only-tool (only on os: windows)"))
+        .stdout(predicate::str::contains("Skipped http://127.0.0.1:1/windows-only (only on os: windows)"));
+}
+
+#[test]
+fn test_cli_cargo_workspace_member() {
+    let workspace = tempfile::tempdir().unwrap();
+    let member = workspace.path().join("crates/api");
+    std::fs::create_dir_all(&member).unwrap();
+    std::fs::write(workspace.path().join("Cargo.toml"), "[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+    std::fs::write(
+        workspace.path().join("Cargo.lock"),
+        "version = 3\n\n[[package]]\nname = \"api\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+    std::fs::write(member.join("Cargo.toml"), "[package]\nname = \"api\"\nversion = \"0.1.0\"\n").unwrap();
+    std::fs::write(member.join(".envcheck.yaml"), "version: \"1\"\ncargo:\n  path: .\n").unwrap();
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_envcheck"));
+    cmd.current_dir(&member);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Cargo.lock is consistent with Cargo.toml (0 dependencies)"));
+}